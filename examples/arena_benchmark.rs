@@ -0,0 +1,178 @@
+//! Compare the speed of `SearchTree` and `ArenaSearchTree` on tic-tac-toe.
+//! Run it with `cargo run --release --example arena_benchmark --features arena`.
+
+use std::{rc::Rc, time::Instant};
+
+use game::{Action, EndStatus, Player, TictactoeGame};
+use mctser::{ArenaSearchTree, RandomRollout, SearchTree};
+
+impl mctser::EndStatus for EndStatus {}
+impl mctser::Action for Action {}
+
+impl mctser::Player<EndStatus> for Player {
+    fn reward_when_outcome_is(&self, outcome: &EndStatus) -> f32 {
+        match outcome {
+            EndStatus::Win(winner) if winner == self => 1.,
+            EndStatus::Win(_) => 0.,
+            EndStatus::Tie => 0.5,
+        }
+    }
+}
+
+impl mctser::GameState for TictactoeGame {
+    type Player = Player;
+    type EndStatus = EndStatus;
+    type Action = Action;
+
+    fn end_status(&self) -> Option<EndStatus> {
+        self.end_status
+    }
+
+    fn player(&self) -> Player {
+        self.player
+    }
+
+    fn possible_actions(&self) -> Vec<Action> {
+        (0..9)
+            .filter(|&i| self.board[i].is_none())
+            .map(Action)
+            .collect()
+    }
+
+    fn act(&self, selection: &Action) -> Self {
+        self.place(selection)
+    }
+}
+
+const SIMULATIONS: u32 = 200_000;
+const SIMULATIONS_PER_MOVE: u32 = 20_000;
+
+/// Time a single search from the empty board and a whole game of self-play.
+fn bench(name: &str, search: impl FnOnce(u32) -> usize, play: impl FnOnce(u32)) {
+    let start = Instant::now();
+    let nodes = search(SIMULATIONS);
+    let searched = start.elapsed();
+    let start = Instant::now();
+    play(SIMULATIONS_PER_MOVE);
+    let played = start.elapsed();
+    println!(
+        "{name:>15}: {SIMULATIONS} simulations in {searched:>10.2?} ({nodes} nodes), \
+        a game of {SIMULATIONS_PER_MOVE} simulations per move in {played:>10.2?}"
+    );
+}
+
+fn main() {
+    for rollout in [false, true] {
+        println!("With rollouts: {rollout}");
+        bench(
+            "SearchTree",
+            |n| {
+                let mut search_tree = SearchTree::new(Rc::new(TictactoeGame::new()));
+                if rollout {
+                    search_tree = search_tree.with_rollout_policy(RandomRollout);
+                }
+                search_tree.search(n);
+                search_tree.node_count()
+            },
+            |n| {
+                let mut search_tree = SearchTree::new(Rc::new(TictactoeGame::new()));
+                if rollout {
+                    search_tree = search_tree.with_rollout_policy(RandomRollout);
+                }
+                while search_tree.get_game_state().end_status.is_none() {
+                    let selected = search_tree.search(n).unwrap();
+                    search_tree.renew(&selected).unwrap();
+                }
+            },
+        );
+        bench(
+            "ArenaSearchTree",
+            |n| {
+                let mut search_tree = ArenaSearchTree::new(TictactoeGame::new());
+                if rollout {
+                    search_tree = search_tree.with_rollout_policy(RandomRollout);
+                }
+                search_tree.search(n);
+                search_tree.node_count()
+            },
+            |n| {
+                let mut search_tree = ArenaSearchTree::new(TictactoeGame::new());
+                if rollout {
+                    search_tree = search_tree.with_rollout_policy(RandomRollout);
+                }
+                while search_tree.get_game_state().end_status.is_none() {
+                    let selected = search_tree.search(n).unwrap();
+                    search_tree.renew(&selected).unwrap();
+                }
+            },
+        );
+    }
+}
+
+mod game {
+    #[derive(Clone, Copy)]
+    pub enum EndStatus {
+        Win(Player),
+        Tie,
+    }
+
+    #[derive(PartialEq, Eq, Clone, Copy)]
+    pub enum Player {
+        X,
+        O,
+    }
+
+    #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    pub struct Action(pub usize);
+
+    /// The board is stored row by row, so that a state is cheap to copy.
+    pub struct TictactoeGame {
+        pub board: [Option<Player>; 9],
+        pub player: Player,
+        pub end_status: Option<EndStatus>,
+    }
+
+    const LINES: [[usize; 3]; 8] = [
+        [0, 1, 2],
+        [3, 4, 5],
+        [6, 7, 8],
+        [0, 3, 6],
+        [1, 4, 7],
+        [2, 5, 8],
+        [0, 4, 8],
+        [2, 4, 6],
+    ];
+
+    impl TictactoeGame {
+        pub fn new() -> Self {
+            Self {
+                board: [None; 9],
+                player: Player::X,
+                end_status: None,
+            }
+        }
+
+        pub fn place(&self, selection: &Action) -> TictactoeGame {
+            let mut board = self.board;
+            board[selection.0] = Some(self.player);
+            let won = LINES
+                .iter()
+                .any(|line| line.iter().all(|&i| board[i] == Some(self.player)));
+            let end_status = if won {
+                Some(EndStatus::Win(self.player))
+            } else if board.iter().all(Option::is_some) {
+                Some(EndStatus::Tie)
+            } else {
+                None
+            };
+            TictactoeGame {
+                board,
+                player: match self.player {
+                    Player::X => Player::O,
+                    Player::O => Player::X,
+                },
+                end_status,
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::rc::Rc;
+
+use game::{Agent, EndStatus, Gridworld, Move};
+
+impl mctser::EndStatus for EndStatus {}
+impl mctser::Action for Move {}
+
+impl mctser::Player<EndStatus> for Agent {
+    fn reward_when_outcome_is(&self, outcome: &EndStatus) -> f32 {
+        match outcome {
+            EndStatus::Goal => 1.,
+            EndStatus::Pit | EndStatus::OutOfTime => 0.,
+        }
+    }
+}
+
+impl mctser::GameState for Gridworld {
+    type Player = Agent;
+    type EndStatus = EndStatus;
+    type Action = Move;
+
+    fn end_status(&self) -> Option<EndStatus> {
+        self.end_status
+    }
+
+    fn player(&self) -> Agent {
+        Agent
+    }
+
+    fn possible_actions(&self) -> Vec<Move> {
+        Move::ALL
+            .into_iter()
+            .filter(|selection| self.target(selection).is_some())
+            .collect()
+    }
+
+    fn act(&self, selection: &Move) -> Self {
+        self.step(selection)
+    }
+}
+
+/// Every step costs a little, so the shortest safe path to the goal is worth the most.
+impl mctser::StepReward<Move> for Gridworld {
+    fn step_reward(&self, _selection: &Move) -> f32 {
+        -0.05
+    }
+}
+
+fn main() {
+    let mut world = Rc::new(Gridworld::new());
+    let mut search_tree = mctser::SearchTree::new(world.clone()).with_step_rewards();
+
+    world.draw();
+    while world.end_status.is_none() {
+        let selected = search_tree.search(5000).unwrap();
+        search_tree.renew(&selected).unwrap();
+        world = search_tree.get_game_state();
+        println!("{:?}", selected);
+        world.draw();
+    }
+    println!(
+        "{:?} after {} steps",
+        world.end_status.unwrap(),
+        world.steps
+    );
+}
+
+mod game {
+    const SIZE: usize = 4;
+    const GOAL: (usize, usize) = (3, 3);
+    const PITS: [(usize, usize); 4] = [(1, 1), (1, 2), (2, 2), (3, 0)];
+    const MAX_STEPS: usize = 10;
+
+    #[derive(Clone, Copy, Debug)]
+    pub enum EndStatus {
+        Goal,
+        Pit,
+        OutOfTime,
+    }
+
+    #[derive(PartialEq, Eq, Clone, Copy)]
+    pub struct Agent;
+
+    #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    pub enum Move {
+        Up,
+        Down,
+        Left,
+        Right,
+    }
+
+    impl Move {
+        pub const ALL: [Move; 4] = [Move::Up, Move::Down, Move::Left, Move::Right];
+    }
+
+    /// An agent walks from the top left corner to the goal at the bottom right, avoiding the pits, within a few steps.
+    pub struct Gridworld {
+        pub position: (usize, usize),
+        pub steps: usize,
+        pub end_status: Option<EndStatus>,
+    }
+
+    impl Gridworld {
+        pub fn new() -> Self {
+            Self {
+                position: (0, 0),
+                steps: 0,
+                end_status: None,
+            }
+        }
+
+        /// The cell reached by the move, if it stays on the grid
+        pub fn target(&self, selection: &Move) -> Option<(usize, usize)> {
+            let (row, col) = self.position;
+            match selection {
+                Move::Up => Some((row.checked_sub(1)?, col)),
+                Move::Down => Some((row + 1, col)).filter(|(row, _)| *row < SIZE),
+                Move::Left => Some((row, col.checked_sub(1)?)),
+                Move::Right => Some((row, col + 1)).filter(|(_, col)| *col < SIZE),
+            }
+        }
+
+        pub fn step(&self, selection: &Move) -> Gridworld {
+            let position = self.target(selection).unwrap();
+            let steps = self.steps + 1;
+            let end_status = if position == GOAL {
+                Some(EndStatus::Goal)
+            } else if PITS.contains(&position) {
+                Some(EndStatus::Pit)
+            } else if steps == MAX_STEPS {
+                Some(EndStatus::OutOfTime)
+            } else {
+                None
+            };
+            Gridworld {
+                position,
+                steps,
+                end_status,
+            }
+        }
+
+        pub fn draw(&self) {
+            for row in 0..SIZE {
+                for col in 0..SIZE {
+                    let cell = match (row, col) {
+                        cell if cell == self.position => 'A',
+                        cell if cell == GOAL => 'G',
+                        cell if PITS.contains(&cell) => '#',
+                        _ => '.',
+                    };
+                    print!("{}", cell);
+                }
+                println!();
+            }
+            println!();
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Compare the speed of the search with and without a cheap `GameState::is_terminal`, on a game of Nim whose
+//! outcome is costly to build. Run it with `cargo run --release --example terminal_benchmark`.
+
+use std::{hint::black_box, rc::Rc, time::Instant};
+
+use mctser::{GameState, RandomRollout, SearchTree, ZeroSumGameState, ZeroSumPlayer};
+
+/// Players take 1 to 3 stones from the pile in turn, and the player who takes the last stone wins.
+#[derive(Clone)]
+struct Nim {
+    pile: u32,
+    first_to_move: bool,
+    /// whether `is_terminal` is the cheap check of the pile rather than the default
+    cheap_check: bool,
+}
+
+#[derive(Clone)]
+struct Outcome {
+    first_wins: bool,
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+struct Take(u32);
+
+impl mctser::EndStatus for Outcome {}
+impl mctser::Action for Take {}
+
+impl ZeroSumGameState<Outcome> for Nim {
+    fn outcome_value(outcome: &Outcome) -> f32 {
+        match outcome.first_wins {
+            true => 1.,
+            false => 0.,
+        }
+    }
+}
+
+impl GameState for Nim {
+    type Player = ZeroSumPlayer<Nim>;
+    type EndStatus = Outcome;
+    type Action = Take;
+
+    fn player(&self) -> ZeroSumPlayer<Nim> {
+        match self.first_to_move {
+            true => ZeroSumPlayer::first(),
+            false => ZeroSumPlayer::second(),
+        }
+    }
+
+    fn end_status(&self) -> Option<Outcome> {
+        // Stands for a costly scoring of the position that decides whether the game is over, like counting a Go board
+        black_box((0..2000).map(|i| i * self.pile).collect::<Vec<u32>>());
+        match self.pile {
+            0 => Some(Outcome {
+                first_wins: !self.first_to_move,
+            }),
+            _ => None,
+        }
+    }
+
+    fn is_terminal(&self) -> bool {
+        match self.cheap_check {
+            true => self.pile == 0,
+            false => self.end_status().is_some(),
+        }
+    }
+
+    fn possible_actions(&self) -> Vec<Take> {
+        (1..=self.pile.min(3)).map(Take).collect()
+    }
+
+    fn act(&self, action: &Take) -> Self {
+        Nim {
+            pile: self.pile - action.0,
+            first_to_move: !self.first_to_move,
+            cheap_check: self.cheap_check,
+        }
+    }
+}
+
+const SIMULATIONS: u32 = 20_000;
+
+fn main() {
+    for cheap_check in [false, true] {
+        let search_tree = SearchTree::new(Rc::new(Nim {
+            pile: 60,
+            first_to_move: true,
+            cheap_check,
+        }))
+        .with_rollout_policy(RandomRollout);
+        let start = Instant::now();
+        search_tree.search(SIMULATIONS);
+        let searched = start.elapsed();
+        let name = match cheap_check {
+            true => "cheap is_terminal",
+            false => "default is_terminal",
+        };
+        println!("{name:>19}: {SIMULATIONS} simulations in {searched:>10.2?}");
+    }
+}
@@ -0,0 +1,120 @@
+use std::rc::Rc;
+
+use game::{Action, EndStatus, NimGame, Player};
+
+impl mctser::EndStatus for EndStatus {}
+impl mctser::Action for Action {}
+
+impl mctser::Player<EndStatus> for Player {
+    fn reward_when_outcome_is(&self, outcome: &EndStatus) -> f32 {
+        let EndStatus::Win(winner) = outcome;
+        if self == winner {
+            1.
+        } else {
+            0.
+        }
+    }
+}
+
+impl mctser::GameState for NimGame {
+    type Player = Player;
+    type EndStatus = EndStatus;
+    type Action = Action;
+
+    fn end_status(&self) -> Option<EndStatus> {
+        self.winner.map(EndStatus::Win)
+    }
+
+    fn player(&self) -> Player {
+        self.player
+    }
+
+    fn num_players(&self) -> usize {
+        3
+    }
+
+    fn current_player_index(&self) -> Option<usize> {
+        Some(self.player as usize)
+    }
+
+    fn is_constant_sum(&self) -> bool {
+        true
+    }
+
+    fn possible_actions(&self) -> Vec<Action> {
+        (1..=self.stones.min(3)).map(Action).collect()
+    }
+
+    fn act(&self, selection: &Action) -> Self {
+        self.take(selection)
+    }
+}
+
+fn main() {
+    let mut game = Rc::new(NimGame::new(12));
+    let mut search_tree = mctser::SearchTree::new(game.clone());
+
+    while game.winner.is_none() {
+        let selected = search_tree.search(5000).unwrap();
+        println!(
+            "{:?} takes {} of {} stones",
+            game.player, selected.0, game.stones
+        );
+        search_tree.renew(&selected).unwrap();
+        game = search_tree.get_game_state();
+    }
+    println!("{:?} wins", game.winner.unwrap());
+}
+
+mod game {
+    #[derive(Clone, Copy)]
+    pub enum EndStatus {
+        Win(Player),
+    }
+
+    #[derive(PartialEq, Eq, Clone, Copy, Debug)]
+    pub enum Player {
+        Player0,
+        Player1,
+        Player2,
+    }
+
+    impl Player {
+        pub fn next(&self) -> Player {
+            match self {
+                Player::Player0 => Player::Player1,
+                Player::Player1 => Player::Player2,
+                Player::Player2 => Player::Player0,
+            }
+        }
+    }
+
+    #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    pub struct Action(pub usize);
+
+    /// Three players take turns to take 1 to 3 stones from a pile; whoever takes the last stone wins.
+    pub struct NimGame {
+        pub stones: usize,
+        pub player: Player,
+        pub winner: Option<Player>,
+    }
+
+    impl NimGame {
+        pub fn new(stones: usize) -> Self {
+            Self {
+                stones,
+                player: Player::Player0,
+                winner: None,
+            }
+        }
+
+        pub fn take(&self, selection: &Action) -> NimGame {
+            let stones = self.stones - selection.0;
+            NimGame {
+                stones,
+                player: self.player.next(),
+                winner: (stones == 0).then_some(self.player),
+            }
+        }
+    }
+}
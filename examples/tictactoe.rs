@@ -20,7 +20,11 @@ impl mctser::Player<EndStatus> for Player {
     }
 }
 
-impl mctser::GameState<Player, EndStatus, Action> for TictactoeGame {
+impl mctser::GameState for TictactoeGame {
+    type Player = Player;
+    type EndStatus = EndStatus;
+    type Action = Action;
+
     fn end_status(&self) -> Option<EndStatus> {
         self.end_status
     }
@@ -80,7 +84,7 @@ mod game {
         }
     }
 
-    #[derive(PartialEq, Eq, Clone, Copy)]
+    #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
     pub struct Action(pub usize, pub usize);
 
     pub struct TictactoeGame {
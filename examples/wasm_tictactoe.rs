@@ -0,0 +1,116 @@
+//! Search a tic-tac-toe move from WebAssembly. The search by a number of simulations needs neither the clock nor threads,
+//! and with a seeded RNG, the same board, budget and seed always give the same move, so games can be replayed exactly.
+//!
+//! Build it with `cargo build --release --example wasm_tictactoe --target wasm32-unknown-unknown`, which gives
+//! `target/wasm32-unknown-unknown/release/examples/wasm_tictactoe.wasm`. It has no imports, so it can be loaded in a browser
+//! with `WebAssembly.instantiateStreaming(fetch("wasm_tictactoe.wasm"))` and called as `instance.exports.best_move(...)`.
+//! It doesn't use `wasm-bindgen`: a crate built with `wasm-pack` would wrap `best_move` in a `#[wasm_bindgen]` function.
+
+use std::rc::Rc;
+
+use mctser::{
+    rand::{rngs::StdRng, SeedableRng},
+    GameState, SearchTree, ZeroSumGameState, ZeroSumPlayer,
+};
+
+/// The cells of the board from the top left, row by row: 0 if empty, 1 for the first player and 2 for the second
+#[derive(Clone)]
+struct Board([u8; 9]);
+
+#[derive(Clone)]
+enum Outcome {
+    Win(u8),
+    Tie,
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+struct Cell(usize);
+
+impl mctser::EndStatus for Outcome {}
+impl mctser::Action for Cell {}
+
+const LINES: [[usize; 3]; 8] = [
+    [0, 1, 2],
+    [3, 4, 5],
+    [6, 7, 8],
+    [0, 3, 6],
+    [1, 4, 7],
+    [2, 5, 8],
+    [0, 4, 8],
+    [2, 4, 6],
+];
+
+impl ZeroSumGameState<Outcome> for Board {
+    fn outcome_value(outcome: &Outcome) -> f32 {
+        match outcome {
+            Outcome::Win(1) => 1.,
+            Outcome::Win(_) => 0.,
+            Outcome::Tie => 0.5,
+        }
+    }
+}
+
+impl Board {
+    /// The mark of the player to move: the first player moves whenever both have placed as many marks
+    fn mark_to_move(&self) -> u8 {
+        let placed = self.0.iter().filter(|&&cell| cell != 0).count();
+        match placed % 2 {
+            0 => 1,
+            _ => 2,
+        }
+    }
+}
+
+impl GameState for Board {
+    type Player = ZeroSumPlayer<Board>;
+    type EndStatus = Outcome;
+    type Action = Cell;
+
+    fn player(&self) -> ZeroSumPlayer<Board> {
+        match self.mark_to_move() {
+            1 => ZeroSumPlayer::first(),
+            _ => ZeroSumPlayer::second(),
+        }
+    }
+
+    fn end_status(&self) -> Option<Outcome> {
+        for [a, b, c] in LINES {
+            if self.0[a] != 0 && self.0[a] == self.0[b] && self.0[b] == self.0[c] {
+                return Some(Outcome::Win(self.0[a]));
+            }
+        }
+        match self.0.contains(&0) {
+            true => None,
+            false => Some(Outcome::Tie),
+        }
+    }
+
+    fn possible_actions(&self) -> Vec<Cell> {
+        (0..9).filter(|&i| self.0[i] == 0).map(Cell).collect()
+    }
+
+    fn act(&self, action: &Cell) -> Self {
+        let mut cells = self.0;
+        cells[action.0] = self.mark_to_move();
+        Board(cells)
+    }
+}
+
+/// The best cell for the player to move on `board`, with two bits per cell from the lowest ones (0 if empty, 1 for the
+/// first player and 2 for the second), found by `simulations` simulations with the RNG seeded by `seed`.
+/// Returns -1 if the board is invalid or the game has ended.
+#[no_mangle]
+pub extern "C" fn best_move(board: u32, simulations: u32, seed: u64) -> i32 {
+    let mut cells = [0; 9];
+    for (i, cell) in cells.iter_mut().enumerate() {
+        *cell = (board >> (2 * i) & 0b11) as u8;
+        if *cell > 2 {
+            return -1;
+        }
+    }
+    let search_tree = SearchTree::new(Rc::new(Board(cells))).with_rng(StdRng::seed_from_u64(seed));
+    match search_tree.search(simulations) {
+        Some(Cell(cell)) => cell as i32,
+        None => -1,
+    }
+}
@@ -0,0 +1,329 @@
+//! A search tree stored in an arena, enabled by the `arena` feature.
+
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::{cell::OnceCell, ops::Range};
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+use crate::{
+    actions_of, argmax, end_status_of, priors_of, sample_weighted, uct, ChildStats, Evaluator,
+    Float, GameState, PlayerRewards, RenewError, RolloutPolicy, TransitionKind, TreePolicy,
+    NO_POSSIBLE_ACTIONS,
+};
+
+type SendPriorsFn<G, A> = dyn Fn(&G) -> Vec<(A, f32)> + Send;
+
+enum LeafEvaluation<G: GameState> {
+    Expand,
+    Rollout(Box<dyn RolloutPolicy<G> + Send>),
+    Evaluate(Box<dyn Evaluator<G> + Send>),
+}
+
+/// A node of [`ArenaSearchTree`], which refers to its children by their indices in the arena.
+struct ArenaNode<G, E, A> {
+    state: G,
+    last_action: Option<A>,
+    /// indices of the children, which are stored next to each other
+    children: Range<usize>,
+    /// cached result of `state.end_status()`
+    end_status: OnceCell<Option<E>>,
+
+    /// times of win, i.e. the rewards of the player to move at the parent node, or at the root node itself
+    wi: Float,
+    /// times of selection
+    ni: Float,
+    /// sum of the squares of the rewards counted in `wi`
+    sum_sq: Float,
+    /// prior probability of the last action, or its probability if the parent is a chance state
+    prior: f32,
+}
+
+impl<G, E, A> ArenaNode<G, E, A> {
+    fn new(state: G, last_action: Option<A>, prior: f32) -> Self {
+        ArenaNode {
+            state,
+            last_action,
+            children: 0..0,
+            end_status: OnceCell::new(),
+            wi: 0.,
+            ni: 0.,
+            sum_sq: 0.,
+            prior,
+        }
+    }
+
+    fn mean_value(&self) -> Float {
+        match self.ni {
+            ni if ni > 0. => self.wi / ni,
+            _ => 0.,
+        }
+    }
+}
+
+/// [`ArenaSearchTree`] is a search tree whose nodes all live in one `Vec` and refer to their children by index,
+/// instead of the `Rc<RefCell<Node>>`s of [`crate::SearchTree`]. A simulation descends the tree without reference
+/// counting or runtime borrow checks, and the tree is `Send` if the game state, the end status and the actions are,
+/// so it can be moved to another thread; the configured policies need to be `Send` for that.
+///
+/// It offers UCT-style search with the tree policy, the leaf evaluation and the priors of [`crate::SearchTree`],
+/// but none of its other options. [`ArenaSearchTree::renew`] copies the retained subtree into a new arena,
+/// which costs time proportional to its size but frees the rest of the tree all at once.
+pub struct ArenaSearchTree<G: GameState> {
+    /// the nodes, the root first
+    nodes: Vec<ArenaNode<G, G::EndStatus, G::Action>>,
+    tree_policy: Box<dyn TreePolicy + Send>,
+    leaf_evaluation: LeafEvaluation<G>,
+    priors: Option<Box<SendPriorsFn<G, G::Action>>>,
+    rng: Box<dyn RngCore + Send>,
+}
+
+impl<G: GameState> ArenaSearchTree<G> {
+    /// Create a new search tree.
+    pub fn new(game_state: G) -> Self {
+        ArenaSearchTree {
+            nodes: vec![ArenaNode::new(game_state, None, 1.)],
+            tree_policy: Box::new(uct),
+            leaf_evaluation: LeafEvaluation::Expand,
+            priors: None,
+            rng: Box::new(StdRng::seed_from_u64(0)),
+        }
+    }
+
+    /// Set the tree policy. By default, it is UCT tree policy.
+    pub fn with_tree_policy(mut self, tree_policy: impl TreePolicy + Send + 'static) -> Self {
+        self.tree_policy = Box::new(tree_policy);
+        self
+    }
+
+    /// Set the rollout policy used to evaluate newly reached leaves, replacing the evaluator if one was set.
+    pub fn with_rollout_policy(
+        mut self,
+        rollout_policy: impl RolloutPolicy<G> + Send + 'static,
+    ) -> Self {
+        self.leaf_evaluation = LeafEvaluation::Rollout(Box::new(rollout_policy));
+        self
+    }
+
+    /// Set the evaluator used to evaluate newly reached leaves instead of rollouts, replacing the rollout policy if one was set.
+    pub fn with_evaluator(mut self, evaluator: impl Evaluator<G> + Send + 'static) -> Self {
+        self.leaf_evaluation = LeafEvaluation::Evaluate(Box::new(evaluator));
+        self
+    }
+
+    /// Set the callback giving the prior probability of each possible action at a state, see [`crate::SearchTree::with_priors`].
+    pub fn with_priors(
+        mut self,
+        priors: impl Fn(&G) -> Vec<(G::Action, f32)> + Send + 'static,
+    ) -> Self {
+        self.priors = Some(Box::new(priors));
+        self
+    }
+
+    /// Set the random number generator used by the search, see [`crate::SearchTree::with_rng`].
+    pub fn with_rng(mut self, rng: impl RngCore + Send + 'static) -> Self {
+        self.rng = Box::new(rng);
+        self
+    }
+
+    /// The end status of the state of node `index`, which is only computed once
+    fn end_status(&self, index: usize) -> Option<&G::EndStatus> {
+        let node = &self.nodes[index];
+        node.end_status
+            .get_or_init(|| end_status_of(&node.state))
+            .as_ref()
+    }
+
+    /// Expand all possible actions of node `index`, or its chance outcomes if it is a chance state.
+    fn expand(&mut self, index: usize) {
+        let state = &self.nodes[index].state;
+        let children = match state.transition_kind() {
+            TransitionKind::Chance => state.chance_outcomes(),
+            TransitionKind::Decision => {
+                let actions = actions_of(state);
+                let priors = self
+                    .priors
+                    .as_deref()
+                    .map(|priors| priors as &crate::PriorsFn<'_, G, G::Action>);
+                let priors = priors_of(state, &actions, priors);
+                actions.into_iter().zip(priors).collect()
+            }
+        };
+        let children: Vec<_> = children
+            .into_iter()
+            .map(|(action, prior)| ArenaNode::new(state.act(&action), Some(action), prior))
+            .collect();
+        let start = self.nodes.len();
+        self.nodes.extend(children);
+        self.nodes[index].children = start..self.nodes.len();
+    }
+
+    /// Select a child of node `index`, at `depth` below the root, by the tree policy.
+    fn select(&self, index: usize, depth: u32) -> Option<usize> {
+        let node = &self.nodes[index];
+        let children = node.children.clone();
+        let unvisited: Vec<_> = children
+            .clone()
+            .filter(|&child| self.nodes[child].ni == 0.)
+            .map(|child| (child, 0.))
+            .collect();
+        if !unvisited.is_empty() {
+            return argmax(unvisited, None);
+        }
+
+        let scored = children
+            .map(|child| {
+                let child_node = &self.nodes[child];
+                let value = self.tree_policy.score(&ChildStats {
+                    wi: child_node.wi,
+                    ni: child_node.ni,
+                    sum_sq: child_node.sum_sq,
+                    np: node.ni,
+                    prior: Float::from(child_node.prior),
+                    amaf_wi: 0.,
+                    amaf_ni: 0.,
+                    ref_amaf_wi: 0.,
+                    ref_amaf_ni: 0.,
+                    minimax_value: child_node.mean_value(),
+                    min_reward: 0.,
+                    max_reward: 1.,
+                    depth,
+                });
+                (child, value)
+            })
+            .collect();
+        argmax(scored, None)
+    }
+
+    fn sample_chance_outcome(&mut self, index: usize) -> Option<usize> {
+        let weighted = self.nodes[index]
+            .children
+            .clone()
+            .map(|child| (child, self.nodes[child].prior))
+            .collect();
+        sample_weighted(weighted, &mut *self.rng)
+    }
+
+    /// Simulate a game play from the root, descending the tree with a path of indices like [`crate::Node`] does.
+    fn simulate(&mut self) -> G::EndStatus {
+        let state = &self.nodes[0].state;
+        let mut path = vec![(0, state.player(), state.current_player_index())];
+        let outcome = loop {
+            let index = path.last().unwrap().0;
+            if let Some(outcome) = self.end_status(index) {
+                break outcome.clone();
+            }
+            let node = &self.nodes[index];
+            match &self.leaf_evaluation {
+                LeafEvaluation::Rollout(rollout_policy) if node.ni == 0. => {
+                    break rollout_policy.rollout(&node.state, &mut *self.rng);
+                }
+                LeafEvaluation::Evaluate(evaluator) if node.ni == 0. => {
+                    break evaluator.evaluate(&node.state);
+                }
+                _ => {}
+            }
+
+            if node.children.is_empty() {
+                self.expand(index);
+            }
+            let selected = match self.nodes[index].state.transition_kind() {
+                TransitionKind::Decision => self.select(index, path.len() as u32 - 1),
+                TransitionKind::Chance => self.sample_chance_outcome(index),
+            };
+            let selected = selected.expect(NO_POSSIBLE_ACTIONS);
+            let state = &self.nodes[index].state;
+            path.push((selected, state.player(), state.current_player_index()));
+        };
+
+        let mut rewards = PlayerRewards::new(self.nodes[0].state.num_players());
+        for (index, player, player_index) in path.iter().rev() {
+            let reward = Float::from(rewards.reward_of(player, *player_index, &outcome));
+            let node = &mut self.nodes[*index];
+            node.ni += 1.;
+            node.wi += reward;
+            node.sum_sq += reward * reward;
+        }
+        outcome
+    }
+
+    /// Search for the best action by running `n` simulations, see [`crate::SearchTree::search`].
+    pub fn search(&mut self, n: u32) -> Option<G::Action> {
+        for _ in 0..n {
+            self.simulate();
+        }
+        self.best_action()
+    }
+
+    /// The most visited action of the root.
+    pub fn best_action(&self) -> Option<G::Action> {
+        let scored = self.nodes[0]
+            .children
+            .clone()
+            .map(|child| (child, self.nodes[child].ni))
+            .collect();
+        let selected = argmax(scored, None)?;
+        self.nodes[selected].last_action.clone()
+    }
+
+    /// The action, times of selection, and mean reward of each child of the root, in the order of [`GameState::order_actions`].
+    pub fn root_children(&self) -> Vec<(G::Action, Float, Float)> {
+        self.nodes[0]
+            .children
+            .clone()
+            .filter_map(|child| {
+                let node = &self.nodes[child];
+                Some((node.last_action.clone()?, node.ni, node.mean_value()))
+            })
+            .collect()
+    }
+
+    /// Move to the next state and renew the root node with given action. The subtree of the action is moved into
+    /// a new arena, keeping its statistics, and the rest of the tree is dropped.
+    pub fn renew(&mut self, action: &G::Action) -> Result<(), RenewError> {
+        if self.end_status(0).is_some() {
+            return Err(RenewError::GameAlreadyEnded);
+        }
+        if self.nodes[0].children.is_empty() {
+            self.expand(0);
+        }
+        let new_root = self.nodes[0]
+            .children
+            .clone()
+            .find(|&child| self.nodes[child].last_action.as_ref() == Some(action))
+            .ok_or(RenewError::ActionNotAvailable)?;
+
+        // Breadth first, so that the children of each node stay next to each other in the new arena
+        let mut order = vec![new_root];
+        let mut first_children = vec![];
+        let mut i = 0;
+        while i < order.len() {
+            first_children.push(order.len());
+            order.extend(self.nodes[order[i]].children.clone());
+            i += 1;
+        }
+        let mut old_nodes: Vec<_> = core::mem::take(&mut self.nodes)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.nodes = order
+            .into_iter()
+            .zip(first_children)
+            .map(|(old, first_child)| {
+                let mut node = old_nodes[old].take().unwrap();
+                node.children = first_child..first_child + node.children.len();
+                node
+            })
+            .collect();
+        Ok(())
+    }
+
+    /// The number of nodes in the tree.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Get the current game state.
+    pub fn get_game_state(&self) -> &G {
+        &self.nodes[0].state
+    }
+}
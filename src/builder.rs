@@ -0,0 +1,209 @@
+use alloc::{rc::Rc, vec::Vec};
+
+use rand::RngCore;
+
+use crate::{
+    uct_with_c, Backup, Evaluator, FinalSelection, Float, GameState, RolloutPolicy, SearchTree,
+    SimultaneousMoves, StepReward, Transposable, TreePolicy,
+};
+
+/// A builder for [`SearchTree`], created by [`SearchTree::builder`]. Each method sets one option of the search tree,
+/// documented on the corresponding `with_*` method of [`SearchTree`], and [`SearchTreeBuilder::build`] returns the tree.
+pub struct SearchTreeBuilder<G: GameState> {
+    search_tree: SearchTree<G>,
+}
+
+impl<G: GameState> SearchTree<G> {
+    /// Start building a search tree for the given game state.
+    pub fn builder(game_state: Rc<G>) -> SearchTreeBuilder<G> {
+        SearchTreeBuilder {
+            search_tree: SearchTree::new(game_state),
+        }
+    }
+}
+
+impl<G: GameState> SearchTreeBuilder<G> {
+    /// See [`SearchTree::with_tree_policy`].
+    pub fn tree_policy(self, tree_policy: impl TreePolicy + 'static) -> Self {
+        self.map(|search_tree| search_tree.with_tree_policy(tree_policy))
+    }
+
+    /// Use the UCT tree policy with the exploration constant `c`, see [`uct_with_c`].
+    pub fn exploration(self, c: Float) -> Self {
+        self.tree_policy(uct_with_c(c))
+    }
+
+    /// See [`SearchTree::with_exploration_schedule`].
+    pub fn exploration_schedule(self, schedule: impl Fn(u32, u32) -> f32 + 'static) -> Self {
+        self.map(|search_tree| search_tree.with_exploration_schedule(schedule))
+    }
+
+    /// See [`SearchTree::with_rollout_policy`].
+    pub fn rollout_policy(self, rollout_policy: impl RolloutPolicy<G> + 'static) -> Self {
+        self.map(|search_tree| search_tree.with_rollout_policy(rollout_policy))
+    }
+
+    /// See [`SearchTree::with_evaluator`].
+    pub fn evaluator(self, evaluator: impl Evaluator<G> + 'static) -> Self {
+        self.map(|search_tree| search_tree.with_evaluator(evaluator))
+    }
+
+    /// See [`SearchTree::with_rollout_depth_limit`].
+    pub fn rollout_depth_limit(
+        self,
+        max_depth: u32,
+        estimate: impl Evaluator<G> + 'static,
+    ) -> Self {
+        self.map(|search_tree| search_tree.with_rollout_depth_limit(max_depth, estimate))
+    }
+
+    /// See [`SearchTree::with_max_rollout_steps`].
+    pub fn max_rollout_steps(self, max_steps: u32, outcome: G::EndStatus) -> Self {
+        self.map(|search_tree| search_tree.with_max_rollout_steps(max_steps, outcome))
+    }
+
+    /// See [`SearchTree::with_implicit_minimax`].
+    pub fn implicit_minimax(self, evaluator: impl Evaluator<G> + 'static) -> Self {
+        self.map(|search_tree| search_tree.with_implicit_minimax(evaluator))
+    }
+
+    /// See [`SearchTree::with_priors`].
+    pub fn priors(self, priors: impl Fn(&G) -> Vec<(G::Action, f32)> + 'static) -> Self {
+        self.map(|search_tree| search_tree.with_priors(priors))
+    }
+
+    /// See [`SearchTree::with_root_dirichlet_noise`].
+    pub fn root_dirichlet_noise(self, alpha: f32, epsilon: f32) -> Self {
+        self.map(|search_tree| search_tree.with_root_dirichlet_noise(alpha, epsilon))
+    }
+
+    /// See [`SearchTree::with_ismcts`].
+    pub fn ismcts(self, ismcts: bool) -> Self {
+        self.map(|search_tree| search_tree.with_ismcts(ismcts))
+    }
+
+    /// See [`SearchTree::with_solver`].
+    pub fn solver(self, solver: bool) -> Self {
+        self.map(|search_tree| search_tree.with_solver(solver))
+    }
+
+    /// See [`SearchTree::with_progressive_widening`].
+    pub fn progressive_widening(self, k: f32, alpha: f32) -> Self {
+        self.map(|search_tree| search_tree.with_progressive_widening(k, alpha))
+    }
+
+    /// See [`SearchTree::with_fpu`].
+    pub fn fpu(self, value: Float) -> Self {
+        self.map(|search_tree| search_tree.with_fpu(value))
+    }
+
+    /// See [`SearchTree::with_fpu_reduction`].
+    pub fn fpu_reduction(self, reduction: Float) -> Self {
+        self.map(|search_tree| search_tree.with_fpu_reduction(reduction))
+    }
+
+    /// See [`SearchTree::with_forced_playouts`].
+    pub fn forced_playouts(self, k: f32) -> Self {
+        self.map(|search_tree| search_tree.with_forced_playouts(k))
+    }
+
+    /// See [`SearchTree::with_discount`].
+    pub fn discount(self, gamma: f32) -> Self {
+        self.map(|search_tree| search_tree.with_discount(gamma))
+    }
+
+    /// See [`SearchTree::with_max_nodes`].
+    pub fn max_nodes(self, max_nodes: usize) -> Self {
+        self.map(|search_tree| search_tree.with_max_nodes(max_nodes))
+    }
+
+    /// See [`SearchTree::with_best_solution`].
+    pub fn best_solution(self, track: bool) -> Self {
+        self.map(|search_tree| search_tree.with_best_solution(track))
+    }
+
+    /// See [`SearchTree::with_final_selection`].
+    pub fn final_selection(self, final_selection: FinalSelection) -> Self {
+        self.map(|search_tree| search_tree.with_final_selection(final_selection))
+    }
+
+    /// See [`SearchTree::with_rng`].
+    pub fn rng(self, rng: impl RngCore + 'static) -> Self {
+        self.map(|search_tree| search_tree.with_rng(rng))
+    }
+
+    /// See [`SearchTree::with_random_tiebreak`].
+    pub fn random_tiebreak(self, random_tiebreak: bool) -> Self {
+        self.map(|search_tree| search_tree.with_random_tiebreak(random_tiebreak))
+    }
+
+    /// Build the search tree.
+    pub fn build(self) -> SearchTree<G> {
+        self.search_tree
+    }
+
+    fn map(self, f: impl FnOnce(SearchTree<G>) -> SearchTree<G>) -> Self {
+        SearchTreeBuilder {
+            search_tree: f(self.search_tree),
+        }
+    }
+}
+
+impl<G> SearchTreeBuilder<G>
+where
+    G: GameState,
+    G::Player: PartialEq,
+{
+    /// See [`SearchTree::with_amaf`].
+    pub fn amaf(self, amaf: bool) -> Self {
+        self.map(|search_tree| search_tree.with_amaf(amaf))
+    }
+
+    /// See [`SearchTree::with_backup`].
+    pub fn backup(self, backup: Backup) -> Self {
+        self.map(|search_tree| search_tree.with_backup(backup))
+    }
+}
+
+impl<G> SearchTreeBuilder<G>
+where
+    G: GameState + Transposable + 'static,
+{
+    /// See [`SearchTree::with_transposition_table`].
+    pub fn transposition_table(self) -> Self {
+        self.map(|search_tree| search_tree.with_transposition_table())
+    }
+}
+
+#[cfg(feature = "action-index")]
+impl<G> SearchTreeBuilder<G>
+where
+    G: GameState,
+    G::Action: core::hash::Hash,
+{
+    /// See [`SearchTree::with_action_index`].
+    pub fn action_index(self) -> Self {
+        self.map(|search_tree| search_tree.with_action_index())
+    }
+}
+
+impl<G> SearchTreeBuilder<G>
+where
+    G: GameState + StepReward<G::Action>,
+    G::Player: PartialEq,
+{
+    /// See [`SearchTree::with_step_rewards`].
+    pub fn step_rewards(self) -> Self {
+        self.map(|search_tree| search_tree.with_step_rewards())
+    }
+}
+
+impl<G> SearchTreeBuilder<G>
+where
+    G: GameState + SimultaneousMoves<G::Player, G::Action>,
+{
+    /// See [`SearchTree::with_simultaneous_moves`].
+    pub fn simultaneous_moves(self) -> Self {
+        self.map(|search_tree| search_tree.with_simultaneous_moves())
+    }
+}
@@ -0,0 +1,88 @@
+use core::{error::Error, fmt::Display};
+
+/// The error returned by [`crate::SearchTree::renew`] and [`crate::SearchTree::renew_fresh`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenewError {
+    /// The action is not among the possible actions of the root state
+    ActionNotAvailable,
+    /// The root state has already ended, so no action can be taken
+    GameAlreadyEnded,
+}
+
+impl Display for RenewError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RenewError::ActionNotAvailable => {
+                write!(f, "the action is not a child of the root node")
+            }
+            RenewError::GameAlreadyEnded => {
+                write!(f, "the game has already ended at the root node")
+            }
+        }
+    }
+}
+
+impl Error for RenewError {}
+
+/// The error returned by [`crate::SearchTree::inject_statistics`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InjectError {
+    /// The action is not among the possible actions of the root state, so it has no child to seed
+    ActionNotAvailable,
+    /// The root state has already ended, so it has no child to seed
+    GameAlreadyEnded,
+}
+
+impl Display for InjectError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            InjectError::ActionNotAvailable => {
+                write!(f, "the action is not a child of the root node")
+            }
+            InjectError::GameAlreadyEnded => {
+                write!(f, "the game has already ended at the root node")
+            }
+        }
+    }
+}
+
+impl Error for InjectError {}
+
+/// The error returned by [`crate::GameState::try_act`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActError {
+    /// The action is not among the possible actions of the state
+    IllegalAction,
+    /// The state has already ended, so no action can be taken
+    GameAlreadyEnded,
+}
+
+impl Display for ActError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ActError::IllegalAction => write!(f, "the action is not possible at the state"),
+            ActError::GameAlreadyEnded => write!(f, "the game has already ended at the state"),
+        }
+    }
+}
+
+impl Error for ActError {}
+
+/// The error returned by [`crate::SearchTree::restore_statistics`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreError {
+    /// Some nodes of the snapshot are no longer in the tree, e.g. since it was renewed or pruned
+    StructureChanged,
+}
+
+impl Display for RestoreError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RestoreError::StructureChanged => {
+                write!(f, "the tree no longer has the nodes of the snapshot")
+            }
+        }
+    }
+}
+
+impl Error for RestoreError {}
@@ -1,279 +1,4579 @@
 #![doc = include_str!("../README.md")]
+#![cfg_attr(not(feature = "std"), no_std)]
 
-use std::{
-    cell::{Cell, RefCell},
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+compile_error!(
+    "mctser needs the `std` feature, or the `alloc` feature to build without the standard library"
+);
+
+extern crate alloc;
+
+use alloc::{
+    boxed::Box,
+    collections::VecDeque,
+    format,
+    rc::{Rc, Weak},
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{
+    cell::{Cell, OnceCell, RefCell},
+    cmp::Ordering,
     fmt::Debug,
-    rc::Rc,
+    hash::Hash,
+    sync::atomic::{self, AtomicBool},
+};
+
+#[cfg(not(feature = "std"))]
+use hashbrown::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "time")]
+use std::time::{Duration, Instant};
+
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+
+pub use rand;
+
+#[cfg(feature = "arena")]
+mod arena;
+#[cfg(feature = "arena")]
+pub use arena::ArenaSearchTree;
+
+mod builder;
+pub use builder::SearchTreeBuilder;
+
+mod error;
+pub use error::{ActError, InjectError, RenewError, RestoreError};
+
+#[cfg(not(any(feature = "std", test)))]
+mod math;
+#[cfg(not(any(feature = "std", test)))]
+use math::FloatMath;
+
+mod policy;
+pub use policy::{
+    depth_aware, grave, implicit_minimax, puct, rave, sp_mcts, ucb1_tuned, ucb_v, uct,
+    uct_normalized, uct_with_c, ChildStats, DepthAware, Grave, ImplicitMinimax, Puct, Rave, SpMcts,
+    TreePolicy, Ucb1Tuned, UcbV, UctNormalized,
+};
+
+#[cfg(feature = "serde")]
+mod serialization;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "std")]
+mod stats;
+#[cfg(feature = "std")]
+pub use stats::{RootStats, StatsHandle};
+
+mod transposition;
+pub use transposition::Transposable;
+use transposition::{TranspositionTable, Transpositions};
+
+mod zero_sum;
+pub use zero_sum::{ZeroSumGameState, ZeroSumPlayer};
+
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "parallel")]
+pub use parallel::{
+    parallel_root_search, parallel_root_search_with, ParallelNode, ParallelSearchTree,
 };
 
 /// The trait for the end status of the game.
 /// Like player1 wins, player2 wins, or tie
-pub trait EndStatus {}
+pub trait EndStatus: Clone {}
 
 /// The trait for the action.
-/// For example, in tictactoe, the action is the coordinate of the next move
+/// For example, in tictactoe, the action is the coordinate of the next move.
+/// Actions that are also `Hash` can index the children of each node, see [`SearchTree::with_action_index`]
 pub trait Action: Eq + Clone {}
 
+/// The type of the statistics of the nodes, like their rewards and visit counts, and of the scores of the tree policies.
+/// It is `f32` by default, whose visit counts stop being exact past 2^24 visits.
+#[cfg(not(feature = "precision64"))]
+pub type Float = f32;
+
+/// The type of the statistics of the nodes, like their rewards and visit counts, and of the scores of the tree policies.
+/// It is `f64` with the `precision64` feature, for long analysis runs whose visit counts exceed 2^24.
+#[cfg(feature = "precision64")]
+pub type Float = f64;
+
 /// The trait for the player.
+/// There may be any number of players: each node accumulates the reward of the player who took the action leading to it,
+/// that is, the player to move at its parent, so every player selects the children that are best for itself.
+/// Two-player zero-sum games can use [`ZeroSumPlayer`] instead, which implements it from the value of each outcome
+/// for the first player, see [`ZeroSumGameState`].
 pub trait Player<E: EndStatus> {
     /// The reward for each player when the game ends with the given outcome.
     fn reward_when_outcome_is(&self, outcome: &E) -> f32;
 }
 
+/// Who chooses the transition out of a game state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionKind {
+    /// The player to move chooses the action
+    Decision,
+    /// The action is drawn at random from [`GameState::chance_outcomes`], like a dice roll or a card draw
+    Chance,
+}
+
 /// The trait for the game state.
-pub trait GameState<P, E, A>
-where
-    P: Player<E>,
-    E: EndStatus,
-    A: Action,
-{
+pub trait GameState {
+    /// The type of the players of the game
+    type Player: Player<Self::EndStatus>;
+    /// The type of the end status of the game
+    type EndStatus: EndStatus;
+    /// The type of the actions of the game
+    type Action: Action;
+
     /// To get the next player
-    fn player(&self) -> P;
+    fn player(&self) -> Self::Player;
+    /// The number of players of the game. By default, it is 2
+    fn num_players(&self) -> usize {
+        2
+    }
+    /// The index of the player to move in `0..num_players`, alongside [`GameState::player`], if the game numbers its
+    /// players. Each node accumulates the rewards of the player who moved to it, and the search routes the rewards of
+    /// a simulation to the nodes by this index: the reward of each player is asked for once per simulation, and every
+    /// node of that player gets it. In debug builds, it checks that the index is in range and that the players of an
+    /// index are rewarded alike. By default, there is no index, and the reward of every node is asked for its own player
+    fn current_player_index(&self) -> Option<usize> {
+        None
+    }
+    /// Whether the rewards of all players sum to the same value at every end of the game, as in zero-sum games.
+    /// If so, and the game implements [`GameState::current_player_index`], the search checks it in debug builds once
+    /// every player has moved on the path of a simulation that ended the game, which catches rewards given from the
+    /// wrong player's perspective. By default, it is `false`, which skips the check
+    fn is_constant_sum(&self) -> bool {
+        false
+    }
     /// Judge if the game is over; if not, return None; if true, return the status of the game result
-    fn end_status(&self) -> Option<E>;
-    /// Get all possible actions for the player at the current state
-    fn possible_actions(&self) -> Vec<A>;
-    /// Get the next state after the player takes the action
-    fn act(&self, action: &A) -> Self;
+    fn end_status(&self) -> Option<Self::EndStatus>;
+    /// Whether the game is over, i.e. whether [`GameState::end_status`] is some. The search checks it first, and only asks
+    /// for the end status once the game is over, so a game whose outcome is costly to build can implement a cheaper check.
+    /// By default, it calls `end_status`
+    fn is_terminal(&self) -> bool {
+        self.end_status().is_some()
+    }
+    /// Get all possible actions for the player at the current state; it must not be empty if the game has not ended.
+    fn possible_actions(&self) -> Vec<Self::Action>;
+    /// Iterate over the possible actions, in the same order as [`GameState::possible_actions`].
+    /// The search consumes it directly where it doesn't need a `Vec`, like in random rollouts,
+    /// so a game generating its moves lazily can override it to skip the `Vec`. By default, it iterates over `possible_actions`
+    fn actions(&self) -> impl Iterator<Item = Self::Action> {
+        self.possible_actions().into_iter()
+    }
+    /// Draw a uniformly random possible action, or `None` if there is none; random rollouts play it at decisions.
+    /// By default, it picks one by reservoir sampling in a single pass over [`GameState::actions`], without collecting
+    /// them, so a game that can draw a random move directly, like an empty cell of a Hex board, can implement it faster
+    fn random_action(&self, rng: &mut dyn RngCore) -> Option<Self::Action> {
+        let mut selected = None;
+        for (i, action) in self.actions().enumerate() {
+            if i == 0 || rng.gen_range(0..=i) == 0 {
+                selected = Some(action);
+            }
+        }
+        selected
+    }
+    /// Get the next state after the player takes the action. The search only calls it with the actions given by the state
+    /// itself, i.e. its possible actions, its pass action or its chance outcomes, and checks the actions passed to methods
+    /// like [`SearchTree::renew`] against them first, so it may panic on any other action
+    fn act(&self, action: &Self::Action) -> Self;
+    /// Like [`GameState::act`], but return an error instead of acting if the game has ended or if the action is not
+    /// one of those given by the state, e.g. to validate the move of a human player before acting on it.
+    /// By default, it checks the action against them before calling `act`
+    fn try_act(&self, action: &Self::Action) -> Result<Self, ActError>
+    where
+        Self: Sized,
+    {
+        if self.is_terminal() {
+            return Err(ActError::GameAlreadyEnded);
+        }
+        match is_available(self, action) {
+            true => Ok(self.act(action)),
+            false => Err(ActError::IllegalAction),
+        }
+    }
+    /// Sort the possible actions most promising first by a heuristic, e.g. captures before quiet moves, when a node is
+    /// expanded. The children of the node keep this order, in which the unvisited ones are selected first and which breaks
+    /// the ties between equal priors in progressive widening. By default, the order of `possible_actions` is kept
+    fn order_actions(&self, actions: &mut Vec<Self::Action>) {
+        let _ = actions;
+    }
+    /// The possible actions with a heuristic score each, e.g. whether a move of connect four wins, blocks a win of the
+    /// opponent or sets one up for it, as a lighter alternative to [`SearchTree::with_priors`] for games with obvious good
+    /// and bad moves. When it returns actions, they are used instead of `possible_actions` when a node is expanded, and
+    /// its children are ordered by their scores, highest first, instead of by [`GameState::order_actions`]: the unvisited
+    /// ones are selected in that order, so a limited budget explores the strong moves first, and progressive widening
+    /// reveals them first unless priors are given. By default, it returns no action and `possible_actions` is used
+    fn heuristic_actions(&self) -> Vec<(Self::Action, f32)> {
+        Vec::new()
+    }
+    /// The action to take when there is no possible action but the game has not ended, like a pass in Go or Othello;
+    /// the search then takes it as the only possible action, and [`GameState::act`] should hand the turn to the next player.
+    /// Ending the game, e.g. after two passes in a row, is up to [`GameState::end_status`]. By default, there is no pass
+    fn pass_action(&self) -> Option<Self::Action> {
+        None
+    }
+    /// Whether the next transition is a decision of the player or a chance event. By default, every state is a decision
+    fn transition_kind(&self) -> TransitionKind {
+        TransitionKind::Decision
+    }
+    /// Get the possible outcomes of a chance event and their probabilities; only called when [`GameState::transition_kind`] is
+    /// [`TransitionKind::Chance`]. The search samples the outcomes with these probabilities instead of selecting them by the
+    /// tree policy, so the statistics of a chance state are the probability-weighted average over its outcomes
+    fn chance_outcomes(&self) -> Vec<(Self::Action, f32)> {
+        vec![]
+    }
+    /// Sample a concrete state consistent with what the player to move can observe, for imperfect-information games
+    /// searched with [`SearchTree::with_ismcts`]. By default, it returns `None`, meaning there is no hidden information
+    fn determinize(&self, _rng: &mut dyn RngCore) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
 }
 
-type RcNode<P, G, E, A> = Rc<RefCell<Node<P, G, E, A>>>;
+/// The trait for game states that give a reward at each step, not only at the end, used by
+/// [`SearchTree::with_step_rewards`]. It is typical of planning problems, where each action may have a cost or a payoff.
+pub trait StepReward<A> {
+    /// The reward of the player to move for taking `action` at this state. It is added to the reward of the end status,
+    /// and to those of the other steps taken by the same player.
+    fn step_reward(&self, action: &A) -> f32;
+}
 
-/// [`Node`] represents a game status in the search tree. It contains the key methods to simulate a game play and find the best move after the node.
-pub struct Node<P, G, E, A> {
-    state: Rc<G>,
-    last_action: Option<A>,
-    child_nodes: RefCell<Vec<RcNode<P, G, E, A>>>,
+/// The trait for game states where several players may move at the same time, like a round of rock-paper-scissors,
+/// used by [`SearchTree::with_simultaneous_moves`]. At such a state, every possible action is a joint action made of one move
+/// of each player moving, and [`GameState::possible_actions`] must return every combination of their moves.
+pub trait SimultaneousMoves<P, A> {
+    /// The move of a single player within a joint action.
+    type Move: PartialEq;
 
-    /// times of win
-    wi: Cell<f32>,
-    /// times of selection
-    ni: Cell<f32>,
+    /// The players moving at once at this state, in the order of their moves in [`SimultaneousMoves::split`].
+    /// A state where a single player moves, or none, is an ordinary turn.
+    fn movers(&self) -> Vec<P>;
 
-    /// policy used to select the child node; the three parameters are wi, ni, and np, which is ni of parent node
-    tree_policy: Rc<dyn Fn(f32, f32, f32) -> f32>,
+    /// Split a joint action of this state into the moves of the players of [`SimultaneousMoves::movers`].
+    fn split(&self, action: &A) -> Vec<Self::Move>;
 }
 
-impl<P, G, E, A> Debug for Node<P, G, E, A>
-where
-    P: Player<E>,
-    G: GameState<P, E, A> + Debug,
-    E: EndStatus + Debug,
-    A: Action,
-{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Node")
-            .field("state", &self.state)
-            .field("wi", &self.wi)
-            .field("ni", &self.ni)
-            .finish()
-    }
+/// Whether two joint actions of a state have the same move of the `i`-th mover
+fn same_move<P, G: SimultaneousMoves<P, A>, A>(state: &G, a: &A, b: &A, i: usize) -> bool {
+    state.split(a)[i] == state.split(b)[i]
 }
 
-impl<P, G, E, A> Node<P, G, E, A>
-where
-    P: Player<E>,
-    G: GameState<P, E, A>,
-    E: EndStatus,
-    A: Action,
-{
-    fn new(state: Rc<G>, tree_policy: Rc<dyn Fn(f32, f32, f32) -> f32>) -> Self {
-        Node {
-            state,
-            last_action: None,
-            child_nodes: RefCell::new(vec![]),
-            wi: Cell::new(0.),
-            ni: Cell::new(0.),
-            tree_policy,
+/// The trait for game states that can be changed in place, which is much cheaper than [`GameState::act`] when the state
+/// is large. It is used by [`InPlaceRollout`], and can be used by custom rollout policies and evaluators to explore
+/// the lines of play from a single state, undoing each move after looking at it.
+pub trait MutableGameState: GameState {
+    /// What it takes to undo a move, e.g. the captured piece
+    type Undo;
+
+    /// Take the action in place, leading to the same state as [`GameState::act`]
+    fn apply(&mut self, action: &Self::Action) -> Self::Undo;
+
+    /// Undo the last action applied, restoring the state it was taken at
+    fn undo(&mut self, undo: Self::Undo);
+}
+
+/// The trait for the rollout (playout) policy.
+/// When a leaf is reached for the first time, the rollout policy plays the game from the leaf state to the end,
+/// and the outcome is used for backpropagation instead of growing the tree all the way to a terminal state.
+pub trait RolloutPolicy<G: GameState> {
+    /// Play the game from the given state until it ends and return the outcome.
+    /// All randomness should be drawn from `rng`, which is the RNG configured by [`SearchTree::with_rng`].
+    fn rollout(&self, state: &G, rng: &mut dyn RngCore) -> G::EndStatus;
+
+    /// Like [`RolloutPolicy::rollout`], but also push the player and the action of every decision taken to `moves`,
+    /// for the AMAF statistics of [`SearchTree::with_amaf`]. By default, no move is recorded.
+    fn rollout_recorded(
+        &self,
+        state: &G,
+        rng: &mut dyn RngCore,
+        moves: &mut Vec<(G::Player, G::Action)>,
+    ) -> G::EndStatus {
+        let _ = moves;
+        self.rollout(state, rng)
+    }
+
+    /// Like [`RolloutPolicy::rollout`], or [`RolloutPolicy::rollout_recorded`] if `moves` is given, but stop once
+    /// `max_depth` actions have been taken without the game ending, and return the evaluation of the state reached by
+    /// `estimate` instead, see [`SearchTree::with_rollout_depth_limit`]. By default, the rollout is never cut off.
+    fn rollout_with_cutoff(
+        &self,
+        state: &G,
+        rng: &mut dyn RngCore,
+        max_depth: u32,
+        estimate: &dyn Evaluator<G>,
+        moves: Option<&mut Vec<(G::Player, G::Action)>>,
+    ) -> G::EndStatus {
+        let _ = (max_depth, estimate);
+        match moves {
+            Some(moves) => self.rollout_recorded(state, rng, moves),
+            None => self.rollout(state, rng),
         }
     }
 
-    fn derive_child(&self, action: A) -> RcNode<P, G, E, A> {
-        Rc::new(RefCell::new(Node {
-            state: Rc::new(self.state.act(&action)),
-            last_action: Some(action),
-            child_nodes: RefCell::new(vec![]),
-            wi: Cell::new(0.),
-            ni: Cell::new(0.),
-            tree_policy: self.tree_policy.clone(),
-        }))
+    /// Whether the rollout policy learns from the simulations of [`SearchTree`], see [`RolloutPolicy::learn`].
+    /// If it does, the decisions of every simulation are recorded, and the rollouts are played by
+    /// [`RolloutPolicy::rollout_recorded`]. By default, it doesn't.
+    fn learns(&self) -> bool {
+        false
     }
 
-    fn find_child(&self, action: &A) -> Option<RcNode<P, G, E, A>> {
-        for node in self.child_nodes.borrow().iter() {
-            if node.borrow().last_action == Some(action.clone()) {
-                return Some(node.clone());
-            }
-        }
-        None
+    /// Learn from a simulation of [`SearchTree`] once its outcome has been backpropagated, if [`RolloutPolicy::learns`]:
+    /// `moves` are the player and the action of every decision taken in the tree and then in the rollout.
+    /// By default, nothing is learned.
+    fn learn(&self, moves: &[(G::Player, G::Action)], outcome: &G::EndStatus) {
+        let _ = (moves, outcome);
     }
+}
 
-    fn select(&self) -> Option<RcNode<P, G, E, A>> {
-        for node in self.child_nodes.borrow().iter() {
-            if node.borrow().ni.get() == 0. {
-                return Some(node.clone());
-            }
+/// An evaluator of states, like the one of the states rollouts are cut off at, see [`SearchTree::with_rollout_depth_limit`]
+type DynEvaluator<'a, G> = dyn Evaluator<G> + 'a;
+
+/// The depth at which rollouts are cut off, and the evaluator of the states they are cut off at
+type RolloutCutoff<'a, G> = (u32, &'a DynEvaluator<'a, G>);
+
+/// A [`RolloutCutoff`] owning its evaluator, as stored by [`SearchTree`]
+type SharedRolloutCutoff<G> = (u32, Rc<DynEvaluator<'static, G>>);
+
+/// Play a rollout from `state`, cut off if `cutoff` is given, and push the decisions taken to `moves` if it is given.
+/// A rollout reaching `step_limit` first ends with the outcome given with it.
+fn play_rollout<G: GameState>(
+    rollout_policy: &(impl RolloutPolicy<G> + ?Sized),
+    state: &G,
+    rng: &mut dyn RngCore,
+    cutoff: Option<RolloutCutoff<'_, G>>,
+    step_limit: Option<(u32, &G::EndStatus)>,
+    moves: Option<&mut Vec<(G::Player, G::Action)>>,
+) -> G::EndStatus {
+    let limit_outcome =
+        step_limit.map(|(max_steps, outcome)| (max_steps, move |_: &G| outcome.clone()));
+    let cutoff = match (cutoff, &limit_outcome) {
+        (Some((max_depth, estimate)), Some((max_steps, _))) if max_depth <= *max_steps => {
+            Some((max_depth, estimate))
+        }
+        (_, Some((max_steps, outcome))) => Some((*max_steps, outcome as &DynEvaluator<'_, G>)),
+        (cutoff, None) => cutoff,
+    };
+    match (cutoff, moves) {
+        (Some((max_depth, estimate)), moves) => {
+            rollout_policy.rollout_with_cutoff(state, rng, max_depth, estimate, moves)
         }
+        (None, Some(moves)) => rollout_policy.rollout_recorded(state, rng, moves),
+        (None, None) => rollout_policy.rollout(state, rng),
+    }
+}
 
-        let mut max_value = f32::MIN;
-        let mut selected_node = None;
-        for node in self.child_nodes.borrow().iter() {
-            let node_borrow = node.borrow();
-            let value =
-                (self.tree_policy)(node_borrow.wi.get(), node_borrow.ni.get(), self.ni.get());
-            if value > max_value {
-                max_value = value;
-                selected_node = Some(node.clone());
-            }
+/// Nested Monte Carlo Search from `state` at `level`, see [`SearchTree::search_nmcs`]: the decisions of the best
+/// playout found, its outcome and its reward for `player`.
+fn nested_search<G: GameState>(
+    state: &G,
+    level: u32,
+    player: &G::Player,
+    rollout_policy: &dyn RolloutPolicy<G>,
+    rng: &mut dyn RngCore,
+) -> Solution<G> {
+    if level == 0 {
+        let mut moves = vec![];
+        let outcome = rollout_policy.rollout_recorded(state, rng, &mut moves);
+        let reward = player.reward_when_outcome_is(&outcome);
+        return (
+            moves.into_iter().map(|(_, action)| action).collect(),
+            outcome,
+            reward,
+        );
+    }
+    let mut played = vec![];
+    let mut best: Option<Solution<G>> = None;
+    let mut current: Option<G> = None;
+    loop {
+        let state = current.as_ref().unwrap_or(state);
+        if let Some(outcome) = end_status_of(state) {
+            return best.unwrap_or_else(|| {
+                let reward = player.reward_when_outcome_is(&outcome);
+                (played, outcome, reward)
+            });
         }
+        let action = match state.transition_kind() {
+            TransitionKind::Decision => {
+                for action in actions_of(state) {
+                    let (moves, outcome, reward) =
+                        nested_search(&state.act(&action), level - 1, player, rollout_policy, rng);
+                    if best.as_ref().is_none_or(|(_, _, best)| reward > *best) {
+                        let moves = played
+                            .iter()
+                            .cloned()
+                            .chain([action])
+                            .chain(moves)
+                            .collect();
+                        best = Some((moves, outcome, reward));
+                    }
+                }
+                let (moves, _, _) = best.as_ref().expect(NO_POSSIBLE_ACTIONS);
+                let action = moves[played.len()].clone();
+                played.push(action.clone());
+                action
+            }
+            // The best sequence found so far followed another outcome, so it is searched anew from the sampled one
+            TransitionKind::Chance => {
+                best = None;
+                sample_weighted(state.chance_outcomes(), rng).expect(NO_POSSIBLE_ACTIONS)
+            }
+        };
+        current = Some(state.act(&action));
+    }
+}
+
+/// The trait for the leaf evaluator, e.g. a heuristic or the value head of a neural network.
+/// When a leaf that has not ended is reached for the first time, its evaluation is backpropagated directly without any rollout;
+/// the evaluation is expressed as an [`EndStatus`] so that it is rewarded through [`Player::reward_when_outcome_is`]
+/// just like true terminal states.
+///
+/// It is implemented for every `Fn(&G) -> G::EndStatus` closure.
+pub trait Evaluator<G: GameState> {
+    /// Evaluate the state of a leaf
+    fn evaluate(&self, state: &G) -> G::EndStatus;
+}
+
+impl<G, F> Evaluator<G> for F
+where
+    G: GameState,
+    F: Fn(&G) -> G::EndStatus,
+{
+    fn evaluate(&self, state: &G) -> G::EndStatus {
+        self(state)
+    }
+}
 
-        selected_node
+/// A [`RolloutPolicy`] that picks a uniformly random action from [`GameState::actions`] until the game ends,
+/// sampling it in one pass over the iterator.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomRollout;
+
+impl<G: GameState> RolloutPolicy<G> for RandomRollout {
+    fn rollout(&self, state: &G, rng: &mut dyn RngCore) -> G::EndStatus {
+        self.play(state, rng, None, None)
     }
 
-    fn select_most_visited(&self) -> Option<RcNode<P, G, E, A>> {
-        let mut times_visted_max = f32::MIN;
-        let mut selected_node = None;
-        for node in self.child_nodes.borrow().iter() {
-            let node_borrow = node.borrow();
-            let times_visted = node_borrow.ni.get();
-            if times_visted > times_visted_max {
-                times_visted_max = times_visted;
-                selected_node = Some(node.clone());
+    fn rollout_recorded(
+        &self,
+        state: &G,
+        rng: &mut dyn RngCore,
+        moves: &mut Vec<(G::Player, G::Action)>,
+    ) -> G::EndStatus {
+        self.play(state, rng, None, Some(moves))
+    }
+
+    fn rollout_with_cutoff(
+        &self,
+        state: &G,
+        rng: &mut dyn RngCore,
+        max_depth: u32,
+        estimate: &dyn Evaluator<G>,
+        moves: Option<&mut Vec<(G::Player, G::Action)>>,
+    ) -> G::EndStatus {
+        self.play(state, rng, Some((max_depth, estimate)), moves)
+    }
+}
+
+impl RandomRollout {
+    fn play<G: GameState>(
+        &self,
+        state: &G,
+        rng: &mut dyn RngCore,
+        cutoff: Option<RolloutCutoff<'_, G>>,
+        mut moves: Option<&mut Vec<(G::Player, G::Action)>>,
+    ) -> G::EndStatus {
+        let mut current: Option<G> = None;
+        let mut depth = 0;
+        loop {
+            let state = current.as_ref().unwrap_or(state);
+            if let Some(outcome) = end_status_of(state) {
+                return outcome;
+            }
+            match cutoff {
+                Some((max_depth, estimate)) if depth == max_depth => {
+                    return estimate.evaluate(state)
+                }
+                _ => {}
             }
+            let action = random_action(state, rng, moves.as_deref_mut());
+            current = Some(state.act(&action));
+            depth += 1;
         }
-
-        selected_node
     }
+}
 
-    fn expand(&self) {
-        if self.is_expanded() {
-            return;
+/// Pick a uniformly random decision, or sample a chance outcome, at a state that has not ended.
+/// A decision is pushed to `moves` if it is given.
+fn random_action<G: GameState>(
+    state: &G,
+    rng: &mut dyn RngCore,
+    moves: Option<&mut Vec<(G::Player, G::Action)>>,
+) -> G::Action {
+    let action = match state.transition_kind() {
+        TransitionKind::Decision => state.random_action(rng).or_else(|| state.pass_action()),
+        TransitionKind::Chance => sample_weighted(state.chance_outcomes(), rng),
+    };
+    let action = action.expect(NO_POSSIBLE_ACTIONS);
+    if let Some(moves) = moves {
+        if state.transition_kind() == TransitionKind::Decision {
+            moves.push((state.player(), action.clone()));
         }
-        for action in self.state.possible_actions().iter() {
-            self.child_nodes
-                .borrow_mut()
-                .push(self.derive_child(action.clone()));
+    }
+    action
+}
+
+/// A [`RolloutPolicy`] that plays like [`RandomRollout`], but on a buffer of its own instead of creating a new state for
+/// every move: the buffer is overwritten with the leaf state by `Clone::clone_from`, which can reuse its allocations,
+/// and the moves are applied to it in place by [`MutableGameState::apply`].
+/// For games with large states, like chess-like boards, it avoids most of the cost of [`GameState::act`] in rollouts.
+pub struct InPlaceRollout<G> {
+    buffer: RefCell<Option<G>>,
+}
+
+impl<G> InPlaceRollout<G> {
+    /// Create the rollout policy; its buffer is allocated by the first rollout.
+    pub fn new() -> Self {
+        InPlaceRollout {
+            buffer: RefCell::new(None),
         }
     }
+}
 
-    fn is_expanded(&self) -> bool {
-        self.child_nodes.borrow().len() > 0
+impl<G> Default for InPlaceRollout<G> {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    fn backpropagate(&self, player: &P, outcome: &E) {
-        self.ni.set(self.ni.get() + 1.);
-        self.wi
-            .set(self.wi.get() + player.reward_when_outcome_is(outcome));
+impl<G> RolloutPolicy<G> for InPlaceRollout<G>
+where
+    G: MutableGameState + Clone,
+{
+    fn rollout(&self, state: &G, rng: &mut dyn RngCore) -> G::EndStatus {
+        self.play(state, rng, None, None)
+    }
+
+    fn rollout_recorded(
+        &self,
+        state: &G,
+        rng: &mut dyn RngCore,
+        moves: &mut Vec<(G::Player, G::Action)>,
+    ) -> G::EndStatus {
+        self.play(state, rng, None, Some(moves))
+    }
+
+    fn rollout_with_cutoff(
+        &self,
+        state: &G,
+        rng: &mut dyn RngCore,
+        max_depth: u32,
+        estimate: &dyn Evaluator<G>,
+        moves: Option<&mut Vec<(G::Player, G::Action)>>,
+    ) -> G::EndStatus {
+        self.play(state, rng, Some((max_depth, estimate)), moves)
     }
+}
 
-    fn simulate(&self, player: &P) -> E {
-        match self.state.end_status() {
-            Some(outcome) => {
-                self.backpropagate(player, &outcome);
-                outcome
+impl<G> InPlaceRollout<G> {
+    fn play(
+        &self,
+        state: &G,
+        rng: &mut dyn RngCore,
+        cutoff: Option<RolloutCutoff<'_, G>>,
+        mut moves: Option<&mut Vec<(G::Player, G::Action)>>,
+    ) -> G::EndStatus
+    where
+        G: MutableGameState + Clone,
+    {
+        let mut buffer = self.buffer.borrow_mut();
+        let buffer = match buffer.as_mut() {
+            Some(buffer) => {
+                buffer.clone_from(state);
+                buffer
             }
-            None => {
-                self.expand();
-                let selected_node = self.select().unwrap();
-                let selected_node = selected_node.borrow_mut();
-                let outcome = selected_node.simulate(&self.state.player());
-                self.backpropagate(player, &outcome);
-                outcome
+            None => buffer.insert(state.clone()),
+        };
+        let mut depth = 0;
+        loop {
+            if let Some(outcome) = end_status_of(buffer) {
+                return outcome;
             }
+            match cutoff {
+                Some((max_depth, estimate)) if depth == max_depth => {
+                    return estimate.evaluate(buffer)
+                }
+                _ => {}
+            }
+            let action = random_action(buffer, rng, moves.as_deref_mut());
+            let _ = buffer.apply(&action);
+            depth += 1;
         }
     }
+}
 
-    pub fn state(&self) -> Rc<G> {
-        self.state.clone()
-    }
+/// A [`RolloutPolicy`] playing the last good reply (LGR), with forgetting: it remembers, for each action, the last reply
+/// to it by a player who won the rollout, and plays it in later rollouts whenever it replies to the same action and is
+/// possible, falling back to a random action like [`RandomRollout`] otherwise. A reply is forgotten once its player
+/// loses a rollout after playing it. The replies are only learned from the actions of the rollouts, with the rewards of
+/// [`SearchTree`]'s solver: a win is a reward of at least 1 and a loss a reward of at most 0. The first decision of a
+/// rollout replies to the last action of the moves recorded before it, which [`SearchTree`] fills with the decisions of
+/// the tree since the policy [learns](RolloutPolicy::learns); a rollout played on its own has nothing to reply to at first.
+pub struct LgrRollout<A> {
+    replies: RefCell<HashMap<A, A>>,
+}
 
-    pub fn child_nodes(&self) -> Vec<RcNode<P, G, E, A>> {
-        self.child_nodes.borrow().clone()
+impl<A> LgrRollout<A> {
+    /// Create the rollout policy without any reply.
+    pub fn new() -> Self {
+        LgrRollout {
+            replies: RefCell::new(HashMap::new()),
+        }
     }
 
-    pub fn wi(&self) -> f32 {
-        self.wi.get()
+    /// The number of actions with a good reply.
+    pub fn reply_count(&self) -> usize {
+        self.replies.borrow().len()
     }
+}
 
-    pub fn ni(&self) -> f32 {
-        self.ni.get()
+impl<A> Default for LgrRollout<A> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-/// [`SearchTree`] is the main struct to use for Monte Carlo Tree Search.
-/// Pass a [`GameState`] wrapped in a `RC` to [`SearchTree::new`] to create a new search tree.
-/// Then you can call [`SearchTree::search`] to get the best action and [`SearchTree::renew`] to move to the next state.
-pub struct SearchTree<P, G, E, A>
+impl<G> RolloutPolicy<G> for LgrRollout<G::Action>
 where
-    P: Player<E>,
-    G: GameState<P, E, A>,
-    E: EndStatus,
-    A: Action,
+    G: GameState,
+    G::Action: Hash,
 {
-    root_node: RcNode<P, G, E, A>,
+    fn rollout(&self, state: &G, rng: &mut dyn RngCore) -> G::EndStatus {
+        self.play(state, rng, None, None)
+    }
+
+    fn rollout_recorded(
+        &self,
+        state: &G,
+        rng: &mut dyn RngCore,
+        moves: &mut Vec<(G::Player, G::Action)>,
+    ) -> G::EndStatus {
+        self.play(state, rng, None, Some(moves))
+    }
+
+    fn rollout_with_cutoff(
+        &self,
+        state: &G,
+        rng: &mut dyn RngCore,
+        max_depth: u32,
+        estimate: &dyn Evaluator<G>,
+        moves: Option<&mut Vec<(G::Player, G::Action)>>,
+    ) -> G::EndStatus {
+        self.play(state, rng, Some((max_depth, estimate)), moves)
+    }
+
+    /// So that the search records the decisions of the tree, the last of which the first decision of the rollout replies to
+    fn learns(&self) -> bool {
+        true
+    }
+}
+
+impl<A: Action + Hash> LgrRollout<A> {
+    fn play<G>(
+        &self,
+        state: &G,
+        rng: &mut dyn RngCore,
+        cutoff: Option<RolloutCutoff<'_, G>>,
+        mut moves: Option<&mut Vec<(G::Player, A)>>,
+    ) -> G::EndStatus
+    where
+        G: GameState<Action = A>,
+    {
+        let mut current: Option<G> = None;
+        let mut depth = 0;
+        let mut previous: Option<A> = moves
+            .as_deref()
+            .and_then(|moves| moves.last())
+            .map(|(_, action)| action.clone());
+        // The decisions taken after another action, with the player who took them and the action they replied to
+        let mut replies = vec![];
+        let outcome = loop {
+            let state = current.as_ref().unwrap_or(state);
+            if let Some(outcome) = end_status_of(state) {
+                break outcome;
+            }
+            match cutoff {
+                Some((max_depth, estimate)) if depth == max_depth => {
+                    break estimate.evaluate(state)
+                }
+                _ => {}
+            }
+            let decision = state.transition_kind() == TransitionKind::Decision;
+            let reply = match (decision, &previous) {
+                (true, Some(previous)) => self
+                    .replies
+                    .borrow()
+                    .get(previous)
+                    .filter(|reply| state.actions().any(|action| action == **reply))
+                    .cloned(),
+                _ => None,
+            };
+            let action = match reply {
+                Some(reply) => {
+                    if let Some(moves) = moves.as_deref_mut() {
+                        moves.push((state.player(), reply.clone()));
+                    }
+                    reply
+                }
+                None => random_action(state, rng, moves.as_deref_mut()),
+            };
+            if let (true, Some(previous)) = (decision, previous.take()) {
+                replies.push((state.player(), previous, action.clone()));
+            }
+            current = Some(state.act(&action));
+            previous = Some(action);
+            depth += 1;
+        };
+
+        let mut good_replies = self.replies.borrow_mut();
+        for (player, previous, reply) in replies {
+            let reward = player.reward_when_outcome_is(&outcome);
+            if reward >= WIN_REWARD {
+                good_replies.insert(previous, reply);
+            } else if reward <= LOSS_REWARD && good_replies.get(&previous) == Some(&reply) {
+                good_replies.remove(&previous);
+            }
+        }
+        outcome
+    }
+}
+
+/// A [`RolloutPolicy`] sampling the actions by move-average sampling (MAST): it keeps the mean reward of every action
+/// over all the simulations of [`SearchTree`], for the player who took it anywhere in the simulation, and samples each
+/// decision of a rollout from the Gibbs distribution `exp(Q(a) / τ)` over those means with temperature `τ`.
+/// Actions that have never been taken count as wins, so that they are tried. The means are learned by
+/// [`RolloutPolicy::learn`], which only [`SearchTree`] calls; elsewhere, the actions are sampled uniformly.
+pub struct MastRollout<A> {
+    temperature: Float,
+    /// total reward and number of simulations of each action
+    values: RefCell<HashMap<A, (Float, Float)>>,
+}
+
+impl<A> MastRollout<A> {
+    /// Create the rollout policy with the temperature `τ`, which must be positive: the lower it is, the more the actions
+    /// with high means are preferred, e.g. 0.1 to 1 for rewards in `[0, 1]`.
+    pub fn new(temperature: Float) -> Self {
+        MastRollout {
+            temperature,
+            values: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+impl<A: Hash + Eq> MastRollout<A> {
+    /// The mean reward of `action`, or `None` if it has never been taken.
+    pub fn action_value(&self, action: &A) -> Option<Float> {
+        let (wi, ni) = *self.values.borrow().get(action)?;
+        Some(wi / ni)
+    }
 }
 
-impl<P, G, E, A> SearchTree<P, G, E, A>
+impl<G> RolloutPolicy<G> for MastRollout<G::Action>
 where
-    P: Player<E>,
-    G: GameState<P, E, A>,
-    E: EndStatus,
-    A: Action,
+    G: GameState,
+    G::Action: Hash,
 {
-    /// Create a new search tree.
-    pub fn new(game_state: Rc<G>) -> Self {
-        SearchTree {
-            root_node: Rc::new(RefCell::new(Node::new(game_state, Rc::new(uct)))),
+    fn rollout(&self, state: &G, rng: &mut dyn RngCore) -> G::EndStatus {
+        self.play(state, rng, None, None)
+    }
+
+    fn rollout_recorded(
+        &self,
+        state: &G,
+        rng: &mut dyn RngCore,
+        moves: &mut Vec<(G::Player, G::Action)>,
+    ) -> G::EndStatus {
+        self.play(state, rng, None, Some(moves))
+    }
+
+    fn rollout_with_cutoff(
+        &self,
+        state: &G,
+        rng: &mut dyn RngCore,
+        max_depth: u32,
+        estimate: &dyn Evaluator<G>,
+        moves: Option<&mut Vec<(G::Player, G::Action)>>,
+    ) -> G::EndStatus {
+        self.play(state, rng, Some((max_depth, estimate)), moves)
+    }
+
+    fn learns(&self) -> bool {
+        true
+    }
+
+    fn learn(&self, moves: &[(G::Player, G::Action)], outcome: &G::EndStatus) {
+        let mut values = self.values.borrow_mut();
+        for (player, action) in moves {
+            let reward = Float::from(player.reward_when_outcome_is(outcome));
+            let (wi, ni) = values.entry(action.clone()).or_insert((0., 0.));
+            *wi += reward;
+            *ni += 1.;
         }
     }
+}
 
-    /// Set the tree policy function. By default, it is UCT tree policy.
-    pub fn with_tree_policy(self, tree_policy: impl Fn(f32, f32, f32) -> f32 + 'static) -> Self {
-        let mut root_node_borrow = self.root_node.borrow_mut();
-        root_node_borrow.tree_policy = Rc::new(tree_policy);
-        drop(root_node_borrow);
-        self
+impl<A: Action + Hash> MastRollout<A> {
+    fn play<G>(
+        &self,
+        state: &G,
+        rng: &mut dyn RngCore,
+        cutoff: Option<RolloutCutoff<'_, G>>,
+        mut moves: Option<&mut Vec<(G::Player, A)>>,
+    ) -> G::EndStatus
+    where
+        G: GameState<Action = A>,
+    {
+        let mut current: Option<G> = None;
+        let mut depth = 0;
+        loop {
+            let state = current.as_ref().unwrap_or(state);
+            if let Some(outcome) = end_status_of(state) {
+                return outcome;
+            }
+            match cutoff {
+                Some((max_depth, estimate)) if depth == max_depth => {
+                    return estimate.evaluate(state)
+                }
+                _ => {}
+            }
+            let action = match state.transition_kind() {
+                TransitionKind::Decision => {
+                    let action = self.sample(state, rng);
+                    if let Some(moves) = moves.as_deref_mut() {
+                        moves.push((state.player(), action.clone()));
+                    }
+                    action
+                }
+                TransitionKind::Chance => random_action(state, rng, None),
+            };
+            current = Some(state.act(&action));
+            depth += 1;
+        }
     }
 
-    /// Search for the best action.
-    pub fn search(&self, n: u32) -> Option<A> {
-        let root_node = self.root_node.borrow();
-        for _ in 0..n {
-            root_node.simulate(&root_node.state.player());
+    /// Sample a decision from the Gibbs distribution over the means of the actions, in one pass over the iterator.
+    fn sample<G>(&self, state: &G, rng: &mut dyn RngCore) -> A
+    where
+        G: GameState<Action = A>,
+    {
+        let values = self.values.borrow();
+        let mut selected = None;
+        let mut total = 0.;
+        // The weights are `exp((Q(a) - max) / τ)` with the maximum mean seen so far, so that they don't overflow at
+        // low temperatures; when the maximum rises, the total is rescaled to it
+        let mut max = Float::NEG_INFINITY;
+        for action in state.actions() {
+            let value = values.get(&action).map_or(1., |(wi, ni)| wi / ni);
+            if value > max {
+                if max > Float::NEG_INFINITY {
+                    total *= ((max - value) / self.temperature).exp();
+                }
+                max = value;
+            }
+            let weight = ((value - max) / self.temperature).exp();
+            total += weight;
+            // Weighted reservoir sampling, which keeps each action with probability proportional to its weight
+            if selected.is_none() || rng.gen::<Float>() * total < weight {
+                selected = Some(action);
+            }
         }
-        let selected_node = root_node.select_most_visited();
-        selected_node.and_then(|v| v.borrow().last_action.clone())
+        selected
+            .or_else(|| state.pass_action())
+            .expect(NO_POSSIBLE_ACTIONS)
     }
+}
 
-    /// Move to the next state and renew the root node with given action.
-    pub fn renew(&mut self, action: &A) -> Result<(), String> {
-        let root_node = self.root_node.borrow_mut();
-        root_node.expand();
-        drop(root_node);
+type RcNode<G> = Rc<RefCell<Node<G>>>;
+type ChildNodes<G> = Rc<RefCell<Vec<RcNode<G>>>>;
+type WeakNode<G> = Weak<RefCell<Node<G>>>;
+/// The copies of the nodes of a tree by the address of the node they copy
+type NodeCopies<G> = HashMap<*const RefCell<Node<G>>, RcNode<G>>;
 
-        let root_node = self.root_node.borrow();
-        let new_root_node = root_node.find_child(action);
+/// The decisions of a simulation that reached the end of the game, its end status and the reward of the player to move
+/// at the root for it, see [`SearchTree::with_best_solution`] and [`SearchTree::search_nmcs`]
+type Solution<G> = (
+    Vec<<G as GameState>::Action>,
+    <G as GameState>::EndStatus,
+    f32,
+);
 
-        drop(root_node);
+/// Callback giving the prior probability of each possible action at a state
+type PriorsFn<'a, G, A> = dyn Fn(&G) -> Vec<(A, f32)> + 'a;
 
-        if let Some(node) = new_root_node {
-            self.root_node = node;
-            return Ok(());
+/// The callback of [`SearchTree::search_batched`], giving an outcome and the priors of the possible actions of each state
+type BatchEvaluator<'a, G, E, A> = dyn FnMut(&[&G]) -> Vec<(E, Vec<(A, f32)>)> + 'a;
+
+/// [`StepReward::step_reward`] and the equality of players, which tells whose reward each step is
+type StepRewards<P, G, A> = (fn(&G, &A) -> f32, fn(&P, &P) -> bool);
+
+/// The callback of [`SearchTree::with_exploration_schedule`], and the exploration constant it gave for the current simulation
+type ExplorationSchedule = (Rc<dyn Fn(u32, u32) -> f32>, Rc<Cell<Float>>);
+
+/// The UCT tree policy of [`SearchTree::with_exploration_schedule`], whose exploration constant is set before every simulation.
+struct ScheduledUct(Rc<Cell<Float>>);
+
+impl TreePolicy for ScheduledUct {
+    fn score(&self, child: &ChildStats) -> Float {
+        uct_with_c(self.0.get())(child.wi, child.ni, child.np)
+    }
+}
+
+/// How to tell whether two players are the same
+type SamePlayer<G> = fn(&<G as GameState>::Player, &<G as GameState>::Player) -> bool;
+
+/// The exponent of the power mean backup and how to tell whether two players are the same
+type PowerMeanBackup<P> = (Float, fn(&P, &P) -> bool);
+
+/// [`SimultaneousMoves::movers`], and whether two joint actions have the same move of a mover
+type Simultaneous<P, G, A> = (fn(&G) -> Vec<P>, fn(&G, &A, &A, usize) -> bool);
+
+/// How a leaf that has not ended is evaluated when it is reached for the first time
+enum LeafEvaluation<G: GameState> {
+    /// Expand the leaf and keep descending until a terminal state is reached
+    Expand,
+    Rollout(Rc<dyn RolloutPolicy<G>>),
+    Evaluate(Rc<dyn Evaluator<G>>),
+}
+
+impl<G: GameState> Clone for LeafEvaluation<G> {
+    fn clone(&self) -> Self {
+        match self {
+            LeafEvaluation::Expand => LeafEvaluation::Expand,
+            LeafEvaluation::Rollout(rollout_policy) => {
+                LeafEvaluation::Rollout(rollout_policy.clone())
+            }
+            LeafEvaluation::Evaluate(evaluator) => LeafEvaluation::Evaluate(evaluator.clone()),
         }
-        Err("The state is not a child of the root node".to_string())
     }
+}
 
-    /// Get the current game state.
-    pub fn get_game_state(&self) -> Rc<G> {
-        self.root_node.borrow().state.clone()
+impl<G: GameState> LeafEvaluation<G> {
+    /// Whether the leaves are evaluated by a rollout policy learning from the simulations
+    fn learns(&self) -> bool {
+        matches!(self, LeafEvaluation::Rollout(rollout_policy) if rollout_policy.learns())
+    }
+}
+
+/// The panic message when the search reaches a state that has no action to take but has not ended
+const NO_POSSIBLE_ACTIONS: &str =
+    "a state whose `GameState::end_status` is `None` has no possible actions \
+    (or no chance outcomes); such a state should be terminal with an end status, \
+    or have a `GameState::pass_action`";
+
+/// The decisions available at `state`: its heuristic actions by score, if it gives any, or else its possible actions
+/// in the order of [`GameState::order_actions`], or its pass action if there are none.
+fn actions_of<G: GameState>(state: &G) -> Vec<G::Action> {
+    let mut scored = state.heuristic_actions();
+    if !scored.is_empty() {
+        // Stable, so that actions with equal scores keep the order of `heuristic_actions`
+        scored.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+        return scored.into_iter().map(|(action, _)| action).collect();
+    }
+    let mut actions: Vec<_> = state.actions().collect();
+    match actions.is_empty() {
+        true => state.pass_action().into_iter().collect(),
+        false => {
+            state.order_actions(&mut actions);
+            actions
+        }
     }
+}
 
-    /// Get the root node.
-    pub fn root_node(&self) -> RcNode<P, G, E, A> {
-        self.root_node.clone()
+/// The end status of `state`, which is only asked for if [`GameState::is_terminal`].
+fn end_status_of<G: GameState>(state: &G) -> Option<G::EndStatus> {
+    match state.is_terminal() {
+        true => state.end_status(),
+        false => None,
+    }
+}
+
+/// Whether `action` can be taken at `state`, as a decision or as a chance outcome.
+fn is_available<G: GameState>(state: &G, action: &G::Action) -> bool {
+    match state.transition_kind() {
+        TransitionKind::Decision => actions_of(state).contains(action),
+        TransitionKind::Chance => state.chance_outcomes().iter().any(|(a, _)| a == action),
+    }
+}
+
+/// The rewards the solver treats as a win and as a loss.
+const WIN_REWARD: f32 = 1.;
+const LOSS_REWARD: f32 = 0.;
+
+/// Scores within this distance of the best score are considered tied.
+const TIE_EPSILON: Float = 1e-6;
+
+/// The number of root actions listed by [`SearchTree::summary`].
+const SUMMARY_ACTIONS: usize = 5;
+
+/// Pick the item with the highest score. Scores within [`TIE_EPSILON`] of the maximum are ties,
+/// which are broken by the order of the items, or uniformly at random if `rng` is given.
+/// A NaN score counts as negative infinity, so such an item is only picked if no other item scores higher,
+/// and an item is picked whenever `scored` is not empty; an infinite score wins over every finite one.
+fn argmax<T>(scored: Vec<(T, Float)>, rng: Option<&mut dyn RngCore>) -> Option<T> {
+    let scored: Vec<_> = scored
+        .into_iter()
+        .map(|(item, score)| match score.is_nan() {
+            true => (item, Float::NEG_INFINITY),
+            false => (item, score),
+        })
+        .collect();
+    let max_score = scored
+        .iter()
+        .map(|(_, score)| *score)
+        .fold(Float::NEG_INFINITY, Float::max);
+    let mut ties: Vec<T> = scored
+        .into_iter()
+        .filter(|(_, score)| *score >= max_score - TIE_EPSILON)
+        .map(|(item, _)| item)
+        .collect();
+    if ties.is_empty() {
+        return None;
+    }
+    let index = match rng {
+        Some(rng) => rng.gen_range(0..ties.len()),
+        None => 0,
+    };
+    Some(ties.swap_remove(index))
+}
+
+/// The range of the rewards backpropagated to the nodes, or `[0, 1]` if none has been.
+fn reward_range_of<G: GameState>(nodes: &[RcNode<G>]) -> (Float, Float) {
+    nodes
+        .iter()
+        .filter_map(|node| node.borrow().reward_range.get())
+        .reduce(|(min, max), (other_min, other_max)| (min.min(other_min), max.max(other_max)))
+        .unwrap_or((0., 1.))
+}
+
+/// The priors of `actions` given by the callback, normalized to sum to one over the actions: priors of other actions are
+/// dropped and negative or missing ones count as zero. Without a callback, or if no action has a positive prior, the
+/// priors are uniform.
+fn priors_of<G, A: PartialEq>(
+    state: &G,
+    actions: &[A],
+    priors: Option<&PriorsFn<'_, G, A>>,
+) -> Vec<f32> {
+    let uniform = || vec![1. / actions.len() as f32; actions.len()];
+    let Some(priors) = priors else {
+        return uniform();
+    };
+    let given_priors = priors(state);
+    let priors: Vec<f32> = actions
+        .iter()
+        .map(|action| {
+            given_priors
+                .iter()
+                .find(|(a, _)| a == action)
+                .map_or(0., |(_, prior)| prior.max(0.))
+        })
+        .collect();
+    let total: f32 = priors.iter().sum();
+    match total > 0. && total.is_finite() {
+        true => priors.into_iter().map(|prior| prior / total).collect(),
+        false => uniform(),
+    }
+}
+
+/// Sample an item with probability proportional to its weight.
+fn sample_weighted<T>(mut weighted: Vec<(T, f32)>, rng: &mut dyn RngCore) -> Option<T> {
+    let total: f32 = weighted.iter().map(|(_, weight)| weight).sum();
+    let mut threshold = rng.gen::<f32>() * total;
+    let last = weighted.pop()?;
+    for (item, weight) in weighted {
+        if threshold < weight {
+            return Some(item);
+        }
+        threshold -= weight;
+    }
+    Some(last.0)
+}
+
+/// Sample the gamma distribution with shape `alpha` and scale 1, by the method of Marsaglia and Tsang.
+fn sample_gamma(alpha: f32, rng: &mut dyn RngCore) -> f32 {
+    if alpha < 1. {
+        // Boost the shape above 1 and scale the sample back down
+        let u = rng.gen::<f32>();
+        return sample_gamma(alpha + 1., rng) * u.powf(1. / alpha);
+    }
+    let d = alpha - 1. / 3.;
+    let c = 1. / (9. * d).sqrt();
+    loop {
+        // A standard normal sample, by the Box-Muller transform
+        let radius = (-2. * (1. - rng.gen::<f32>()).ln()).sqrt();
+        let x = radius * (core::f32::consts::TAU * rng.gen::<f32>()).cos();
+        let v = (1. + c * x).powi(3);
+        if v <= 0. {
+            continue;
+        }
+        let u = rng.gen::<f32>();
+        if u.ln() < 0.5 * x * x + d - d * v + d * v.ln() {
+            return d * v;
+        }
+    }
+}
+
+/// Sample the symmetric Dirichlet distribution with concentration `alpha` over `n` items.
+fn sample_dirichlet(alpha: f32, n: usize, rng: &mut dyn RngCore) -> Vec<f32> {
+    let samples: Vec<f32> = (0..n).map(|_| sample_gamma(alpha, rng)).collect();
+    let total: f32 = samples.iter().sum();
+    match total > 0. {
+        true => samples.into_iter().map(|sample| sample / total).collect(),
+        false => vec![1. / n as f32; n],
+    }
+}
+
+/// The first play urgency of the unvisited children, see [`SearchTree::with_fpu`] and [`SearchTree::with_fpu_reduction`].
+#[derive(Debug, Clone, Copy)]
+enum Fpu {
+    /// a fixed value
+    Value(Float),
+    /// the mean value of the visited children, reduced by this times the square root of their total prior
+    Reduction(Float),
+}
+
+/// The rewards of the players for the outcome of a simulation by their index, see [`GameState::current_player_index`],
+/// each one asked for once, from the first player of its index.
+struct PlayerRewards(Vec<Option<f32>>);
+
+impl PlayerRewards {
+    fn new(num_players: usize) -> Self {
+        PlayerRewards(vec![None; num_players])
+    }
+
+    /// The reward of `player`, whose index is `index` if the game numbers its players, for `outcome`.
+    fn reward_of<P: Player<E>, E: EndStatus>(
+        &mut self,
+        player: &P,
+        index: Option<usize>,
+        outcome: &E,
+    ) -> f32 {
+        let Some(index) = index else {
+            return player.reward_when_outcome_is(outcome);
+        };
+        debug_assert!(
+            index < self.0.len(),
+            "the index of the player to move is {index}, but there are {} players",
+            self.0.len()
+        );
+        let Some(routed) = self.0.get_mut(index) else {
+            return player.reward_when_outcome_is(outcome);
+        };
+        match *routed {
+            Some(reward) => {
+                #[cfg(debug_assertions)]
+                {
+                    let own = player.reward_when_outcome_is(outcome);
+                    assert!(
+                        own == reward || own.is_nan(),
+                        "two players of index {index} are rewarded {reward} and {own} for the same outcome: \
+                         GameState::current_player_index should give each player its own index"
+                    );
+                }
+                reward
+            }
+            None => {
+                let reward = player.reward_when_outcome_is(outcome);
+                *routed = Some(reward);
+                reward
+            }
+        }
+    }
+
+    /// Check that the rewards, if every player was rewarded, sum to the same as at the other ends of the game
+    /// in `reward_sum`, see [`GameState::is_constant_sum`].
+    #[cfg(debug_assertions)]
+    fn check_constant_sum(&self, reward_sum: &Cell<Option<f32>>) {
+        for reward in self.0.iter().flatten() {
+            assert!(reward.is_finite(), "the reward of a player is {reward}");
+        }
+        let Some(sum) = self.0.iter().copied().sum::<Option<f32>>() else {
+            return;
+        };
+        match reward_sum.get() {
+            Some(expected) => assert!(
+                (sum - expected).abs() <= 1e-4 * expected.abs().max(1.),
+                "the rewards of the players sum to {sum} at an end of the game, but to {expected} at another: \
+                 Player::reward_when_outcome_is should give the reward of the player it is called on"
+            ),
+            None => reward_sum.set(Some(sum)),
+        }
+    }
+}
+
+/// The settings of the search tree that are passed down the tree during a simulation.
+struct SearchContext<'a, G: GameState> {
+    leaf_evaluation: &'a LeafEvaluation<G>,
+    rollout_cutoff: Option<RolloutCutoff<'a, G>>,
+    /// the most actions a rollout may take, and the outcome of one that reaches it
+    max_rollout_steps: Option<(u32, &'a G::EndStatus)>,
+    priors: Option<&'a PriorsFn<'a, G, G::Action>>,
+    transpositions: Option<&'a dyn Transpositions<G>>,
+    ismcts: bool,
+    solver: bool,
+    widening: Option<(f32, f32)>,
+    fpu: Option<Fpu>,
+    /// the factor of the forced playouts of the root's children, if they are forced
+    forced_playouts: Option<f32>,
+    /// how to tell whether two players are the same, if the AMAF statistics are recorded
+    amaf: Option<SamePlayer<G>>,
+    /// factor applied to the rewards per level they are backpropagated up
+    discount: f32,
+    /// the exponent of the power mean and how to tell whether two players are the same, if values are backed up by it
+    backup: Option<PowerMeanBackup<G::Player>>,
+    /// the evaluator of the leaves whose outcomes are backed up by minimax, if they are
+    implicit_minimax: Option<&'a DynEvaluator<'a, G>>,
+    /// the reward of each step and how to tell whether two players are the same, if step rewards are collected
+    step_rewards: Option<StepRewards<G::Player, G, G::Action>>,
+    /// how to tell the movers of a state and their moves, if players may move simultaneously
+    simultaneous: Option<Simultaneous<G::Player, G, G::Action>>,
+    random_tiebreak: bool,
+    /// number of nodes in the tree, and the most there may be
+    nodes: &'a Cell<usize>,
+    max_nodes: Option<usize>,
+    /// the child of the root every simulation descends into, instead of the one selected by the tree policy
+    root_child: Option<&'a RcNode<G>>,
+    /// whether descents stop at unexpanded leaves, which are evaluated in batches
+    defer_leaves: bool,
+    /// the best simulation to the end of the game found from the root, if it is tracked
+    best_solution: Option<&'a RefCell<Option<Solution<G>>>>,
+    /// the sum of the rewards of the players at the ends of the game, once one has been reached by every player
+    #[cfg(debug_assertions)]
+    reward_sum: &'a Cell<Option<f32>>,
+    rng: &'a mut dyn RngCore,
+}
+
+impl<G: GameState> SearchContext<'_, G> {
+    fn at_capacity(&self) -> bool {
+        self.max_nodes
+            .is_some_and(|max_nodes| self.nodes.get() >= max_nodes)
+    }
+
+    fn tiebreak_rng(&mut self) -> Option<&mut dyn RngCore> {
+        match self.random_tiebreak {
+            true => Some(&mut *self.rng),
+            false => None,
+        }
+    }
+}
+
+/// The nodes a simulation visited on its way down the tree, kept to backpropagate its outcome.
+struct Descent<G: GameState> {
+    /// Each node is rewarded from the perspective of the player who took its last action; the root, from its own player's
+    path: Vec<(RcNode<G>, G::Player)>,
+    /// The index of the player of each node of the path, if the game numbers its players
+    player_indices: Vec<Option<usize>>,
+    /// The reward of the step leading to each node of the path, if step rewards are collected
+    step_rewards: Vec<f32>,
+    /// The decisions taken during the simulation, and how many of them were taken before leaving each node of the path
+    moves: Vec<(G::Player, G::Action)>,
+    first_moves: Vec<usize>,
+    /// The state reached, or a determinization of it in ISMCTS
+    state: Rc<G>,
+    /// Whether the decisions lead to the end of the game, rather than to a leaf whose outcome was evaluated or solved
+    ended: bool,
+}
+
+impl<G: GameState> Descent<G> {
+    fn leaf(&self) -> &RcNode<G> {
+        &self.path.last().unwrap().0
+    }
+
+    /// Count a visit without reward at every node of the path while the evaluation of the leaf is pending,
+    /// which steers the following descents away from it.
+    fn add_virtual_loss(&self) {
+        for (node, _) in &self.path {
+            let node = node.borrow();
+            node.ni.set(node.ni.get() + 1.);
+        }
+    }
+
+    fn remove_virtual_loss(&self) {
+        for (node, _) in &self.path {
+            let node = node.borrow();
+            node.ni.set(node.ni.get() - 1.);
+        }
+    }
+
+    /// Backpropagate `outcome` along the path from the leaf up to the root.
+    fn backpropagate(&self, outcome: &G::EndStatus, ctx: &SearchContext<G>) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            depth = self.path.len() - 1,
+            reward = self.path[0].1.reward_when_outcome_is(outcome),
+            "backpropagated"
+        );
+        if let Some(same_player) = ctx.amaf {
+            for ((node, _), first) in self.path.iter().zip(&self.first_moves) {
+                node.borrow()
+                    .update_amaf(&self.moves[*first..], same_player, outcome);
+            }
+        }
+
+        // A node can only become solved if its child on the path did
+        let mut solving = ctx.solver;
+        let mut discount = 1.;
+        // The discounted step rewards below the current node, summed by the player who took the steps
+        let mut step_returns: Vec<(&G::Player, f32)> = vec![];
+        let root_state = self.path[0].0.borrow().state.clone();
+        let mut rewards = PlayerRewards::new(root_state.num_players());
+        for (i, (node, player)) in self.path.iter().enumerate().rev() {
+            let mut reward = discount * rewards.reward_of(player, self.player_indices[i], outcome);
+            if let Some((_, same_player)) = ctx.step_rewards {
+                if i > 0 {
+                    match step_returns
+                        .iter_mut()
+                        .find(|(p, _)| same_player(p, player))
+                    {
+                        Some((_, step_return)) => *step_return += self.step_rewards[i],
+                        None => step_returns.push((player, self.step_rewards[i])),
+                    }
+                }
+                if let Some((_, step_return)) =
+                    step_returns.iter().find(|(p, _)| same_player(p, player))
+                {
+                    reward += step_return;
+                }
+                for (_, step_return) in step_returns.iter_mut() {
+                    *step_return *= ctx.discount;
+                }
+            }
+            let node = node.borrow();
+            node.backpropagate(Float::from(reward));
+            if let Some((p, same_player)) = ctx.backup {
+                node.back_up_power_mean(p, same_player(player, &node.state.player()));
+            }
+            if let Some(evaluator) = ctx.implicit_minimax {
+                node.back_up_minimax(evaluator, i + 1 == self.path.len());
+            }
+            if let (Some((movers, _)), Some(parent)) = (ctx.simultaneous, i.checked_sub(1)) {
+                let movers = movers(&self.path[parent].0.borrow().state);
+                if movers.len() > 1 {
+                    node.backpropagate_movers(&movers, discount, outcome);
+                }
+            }
+            discount *= ctx.discount;
+            solving = solving && node.solve();
+        }
+        if let LeafEvaluation::Rollout(rollout_policy) = ctx.leaf_evaluation {
+            if rollout_policy.learns() {
+                rollout_policy.learn(&self.moves, outcome);
+            }
+        }
+        #[cfg(debug_assertions)]
+        if self.ended && root_state.is_constant_sum() {
+            rewards.check_constant_sum(ctx.reward_sum);
+        }
+        if let Some(best_solution) = ctx.best_solution.filter(|_| self.ended) {
+            let reward = self.path[0].1.reward_when_outcome_is(outcome);
+            let mut best_solution = best_solution.borrow_mut();
+            if best_solution
+                .as_ref()
+                .is_none_or(|(_, _, best)| reward > *best)
+            {
+                let moves = self
+                    .moves
+                    .iter()
+                    .map(|(_, action)| action.clone())
+                    .collect();
+                *best_solution = Some((moves, outcome.clone(), reward));
+            }
+        }
+    }
+}
+
+/// [`Node`] represents a game status in the search tree. It contains the key methods to simulate a game play and find the best move after the node.
+pub struct Node<G: GameState> {
+    state: Rc<G>,
+    last_action: Option<G::Action>,
+    /// children, shared with the nodes of the same position when the transposition table is enabled
+    child_nodes: ChildNodes<G>,
+    /// hash of the actions indexing the children, if [`SearchTree::with_action_index`] enabled the index
+    #[cfg(feature = "action-index")]
+    hash_action: Option<fn(&G::Action) -> u64>,
+    /// index of the first child in `child_nodes` with each hash of its last action
+    #[cfg(feature = "action-index")]
+    child_index: Rc<RefCell<HashMap<u64, usize>>>,
+    /// cached result of `state.end_status()`
+    end_status: OnceCell<Option<G::EndStatus>>,
+    /// outcome of the game under optimal play from this node, once proven by the solver
+    solved: OnceCell<G::EndStatus>,
+
+    /// times of win, i.e. the rewards of the player to move at the parent node, or at the root node itself
+    wi: Cell<Float>,
+    /// times of selection
+    ni: Cell<Float>,
+    /// sum of the squares of the rewards counted in `wi`
+    sum_sq: Cell<Float>,
+
+    /// prior probability of the last action, given by the priors callback of the search tree,
+    /// or the probability of the last action if the parent is a chance state
+    prior: Cell<f32>,
+    /// decisions not revealed as children by progressive widening yet, with their priors, the next one last
+    pending: RefCell<Vec<(G::Action, f32)>>,
+    /// times the last action was available when the parent was selected, only counted in ISMCTS
+    availability: Cell<Float>,
+    /// total reward of the simulations where the player to move at the parent took the last action at any later point
+    amaf_wi: Cell<Float>,
+    /// number of the simulations counted in `amaf_wi`
+    amaf_ni: Cell<Float>,
+    /// smallest and largest reward of a single simulation, once visited
+    reward_range: Cell<Option<(Float, Float)>>,
+    /// total reward and sum of the squared rewards of each player moving at the parent, if players move simultaneously there
+    mover_rewards: RefCell<Vec<(Float, Float)>>,
+    /// value for the player to move at the parent backed up from the children by the power mean, once they were visited
+    backed_up: Cell<Option<Float>>,
+    /// heuristic outcome backed up from the leaves by minimax, once visited, see [`SearchTree::with_implicit_minimax`]
+    minimax_outcome: RefCell<Option<G::EndStatus>>,
+
+    /// policy used to select the child node
+    tree_policy: Rc<dyn TreePolicy>,
+
+    /// the node itself, which its children link to as their parent
+    this: WeakNode<G>,
+    /// the node that created this one as its child, or none for the root
+    parent: RefCell<WeakNode<G>>,
+}
+
+impl<G> Debug for Node<G>
+where
+    G: GameState + Debug,
+    G::EndStatus: Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Node")
+            .field("state", &self.state)
+            .field("wi", &self.wi)
+            .field("ni", &self.ni)
+            .finish()
+    }
+}
+
+impl<G: GameState> Drop for Node<G> {
+    fn drop(&mut self) {
+        // Drop the subtree iteratively, so that dropping a deep tree can't overflow the stack either
+        // A subtree shared with other nodes of the same position is left to them
+        let mut child_nodes = match Rc::get_mut(&mut self.child_nodes) {
+            Some(child_nodes) => core::mem::take(child_nodes.get_mut()),
+            None => return,
+        };
+        while let Some(node) = child_nodes.pop() {
+            if let Ok(node) = Rc::try_unwrap(node) {
+                if let Some(grandchildren) = Rc::get_mut(&mut node.into_inner().child_nodes) {
+                    child_nodes.append(grandchildren.get_mut());
+                }
+            }
+        }
+    }
+}
+
+impl<G: GameState> Node<G> {
+    /// Move the node into an `Rc`, linking it to itself and its children to it.
+    fn into_rc(mut self) -> RcNode<G> {
+        Rc::new_cyclic(|this| {
+            self.this = this.clone();
+            for child in self.child_nodes.borrow().iter() {
+                *child.borrow().parent.borrow_mut() = this.clone();
+            }
+            RefCell::new(self)
+        })
+    }
+}
+
+impl<G: GameState> Node<G> {
+    fn new(state: Rc<G>, tree_policy: Rc<dyn TreePolicy>) -> Self {
+        Node {
+            state,
+            last_action: None,
+            child_nodes: Rc::default(),
+            #[cfg(feature = "action-index")]
+            hash_action: None,
+            #[cfg(feature = "action-index")]
+            child_index: Rc::default(),
+            end_status: OnceCell::new(),
+            solved: OnceCell::new(),
+            wi: Cell::new(0.),
+            ni: Cell::new(0.),
+            sum_sq: Cell::new(0.),
+            prior: Cell::new(1.),
+            pending: RefCell::new(vec![]),
+            availability: Cell::new(0.),
+            amaf_wi: Cell::new(0.),
+            amaf_ni: Cell::new(0.),
+            reward_range: Cell::new(None),
+            mover_rewards: RefCell::new(vec![]),
+            backed_up: Cell::new(None),
+            minimax_outcome: RefCell::new(None),
+            tree_policy,
+            this: Weak::new(),
+            parent: RefCell::new(Weak::new()),
+        }
+    }
+
+    fn derive_child(&self, state: &G, action: G::Action, prior: f32) -> RcNode<G> {
+        Node {
+            state: Rc::new(state.act(&action)),
+            last_action: Some(action),
+            child_nodes: Rc::default(),
+            #[cfg(feature = "action-index")]
+            hash_action: self.hash_action,
+            #[cfg(feature = "action-index")]
+            child_index: Rc::default(),
+            end_status: OnceCell::new(),
+            solved: OnceCell::new(),
+            wi: Cell::new(0.),
+            ni: Cell::new(0.),
+            sum_sq: Cell::new(0.),
+            prior: Cell::new(prior),
+            pending: RefCell::new(vec![]),
+            availability: Cell::new(0.),
+            amaf_wi: Cell::new(0.),
+            amaf_ni: Cell::new(0.),
+            reward_range: Cell::new(None),
+            mover_rewards: RefCell::new(vec![]),
+            backed_up: Cell::new(None),
+            minimax_outcome: RefCell::new(None),
+            tree_policy: self.tree_policy.clone(),
+            this: Weak::new(),
+            parent: RefCell::new(self.this.clone()),
+        }
+        .into_rc()
+    }
+
+    /// The end status of the node's state, which is only computed once
+    fn end_status(&self) -> Option<&G::EndStatus> {
+        self.end_status
+            .get_or_init(|| end_status_of(self.state.as_ref()))
+            .as_ref()
+    }
+
+    /// A new node for `state` without statistics, which selects and indexes its children like this one.
+    fn renewed(&self, state: Rc<G>) -> Self {
+        #[allow(unused_mut)]
+        let mut node = Node::new(state, self.tree_policy.clone());
+        #[cfg(feature = "action-index")]
+        {
+            node.hash_action = self.hash_action;
+        }
+        node
+    }
+
+    /// Add `node` to the children, keeping the index by action up to date.
+    fn push_child(&self, node: RcNode<G>) {
+        #[cfg(feature = "action-index")]
+        if let (Some(hash_action), Some(action)) = (self.hash_action, &node.borrow().last_action) {
+            let index = self.child_nodes.borrow().len();
+            self.child_index
+                .borrow_mut()
+                .entry(hash_action(action))
+                .or_insert(index);
+        }
+        self.child_nodes.borrow_mut().push(node);
+    }
+
+    /// Rebuild the index of the children by action, if the node has one.
+    #[cfg(feature = "action-index")]
+    fn index_children(&self) {
+        let Some(hash_action) = self.hash_action else {
+            return;
+        };
+        let mut child_index = HashMap::new();
+        for (index, node) in self.child_nodes.borrow().iter().enumerate() {
+            if let Some(action) = &node.borrow().last_action {
+                child_index.entry(hash_action(action)).or_insert(index);
+            }
+        }
+        *self.child_index.borrow_mut() = child_index;
+    }
+
+    fn find_child(&self, action: &G::Action) -> Option<RcNode<G>> {
+        #[cfg(feature = "action-index")]
+        if let Some(hash_action) = self.hash_action {
+            let index = *self.child_index.borrow().get(&hash_action(action))?;
+            let node = self.child_nodes.borrow().get(index).cloned();
+            if let Some(node) =
+                node.filter(|node| node.borrow().last_action.as_ref() == Some(action))
+            {
+                return Some(node);
+            }
+            // Another action with the same hash, which only the scan below tells apart
+        }
+        for node in self.child_nodes.borrow().iter() {
+            if node.borrow().last_action == Some(action.clone()) {
+                return Some(node.clone());
+            }
+        }
+        None
+    }
+
+    fn select(
+        &self,
+        depth: u32,
+        solver: bool,
+        fpu: Option<Fpu>,
+        reference: Option<&Self>,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Option<RcNode<G>> {
+        let child_nodes = self.child_nodes.borrow();
+        match solver {
+            true => {
+                let choices = self.solver_choices(&child_nodes);
+                self.select_from(&choices, depth, false, fpu, reference, rng)
+            }
+            false => self.select_from(&child_nodes, depth, false, fpu, reference, rng),
+        }
+    }
+
+    /// The children worth choosing from once some of them are solved: a proven win of the player to move if there is one,
+    /// otherwise the children that are not proven losses, or all of them if every child is lost.
+    fn solver_choices(&self, child_nodes: &[RcNode<G>]) -> Vec<RcNode<G>> {
+        let player = self.state.player();
+        let reward_of = |node: &RcNode<G>| {
+            let node = node.borrow();
+            node.solved
+                .get()
+                .map(|outcome| player.reward_when_outcome_is(outcome))
+        };
+        if let Some(win) = child_nodes
+            .iter()
+            .find(|node| reward_of(node).is_some_and(|reward| reward >= WIN_REWARD))
+        {
+            return vec![win.clone()];
+        }
+        let choices: Vec<_> = child_nodes
+            .iter()
+            .filter(|node| reward_of(node).is_none_or(|reward| reward > LOSS_REWARD))
+            .cloned()
+            .collect();
+        match choices.is_empty() {
+            true => child_nodes.to_vec(),
+            false => choices,
+        }
+    }
+
+    /// Try to prove the outcome of the node under optimal play, after its children were updated. A terminal node is
+    /// solved by its end status, and a decision node either by a child that is a proven win of the player to move,
+    /// or by the best of its children once all of them are solved. Chance nodes are never solved.
+    /// Returns whether the node is solved.
+    fn solve(&self) -> bool {
+        if self.solved.get().is_some() {
+            return true;
+        }
+        if let Some(outcome) = self.end_status() {
+            let _ = self.solved.set(outcome.clone());
+            return true;
+        }
+        if self.state.transition_kind() == TransitionKind::Chance {
+            return false;
+        }
+        let player = self.state.player();
+        let child_nodes = self.child_nodes.borrow();
+        let mut best: Option<(G::EndStatus, f32)> = None;
+        for node in child_nodes.iter() {
+            let node = node.borrow();
+            let Some(outcome) = node.solved.get() else {
+                continue;
+            };
+            let reward = player.reward_when_outcome_is(outcome);
+            if best.as_ref().is_none_or(|(_, best)| reward > *best) {
+                best = Some((outcome.clone(), reward));
+            }
+        }
+        let all_solved = child_nodes
+            .iter()
+            .all(|node| node.borrow().solved.get().is_some());
+        // The decisions not revealed by progressive widening yet are unknown
+        let all_solved = all_solved && self.pending.borrow().is_empty();
+        match best {
+            Some((outcome, reward)) if reward >= WIN_REWARD || all_solved => {
+                let _ = self.solved.set(outcome);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Select one of `child_nodes` by the tree policy, this node being at `depth` below the root.
+    /// With `by_availability`, the tree policy is given how many times each child was available instead of how many times
+    /// this node was selected, as ISMCTS requires.
+    /// Children shared through the transposition table may have been visited more often than this node,
+    /// in which case the tree policy is given the sum of their visits instead.
+    ///
+    /// Without `fpu`, or until a child has been visited, the unvisited children are selected first. With it, they are scored
+    /// by the tree policy like the visited ones, as if they had been visited once with their first play urgency as reward.
+    ///
+    /// The reference AMAF statistics of each child are those of the child with the same action below `reference`,
+    /// or its own if there is no reference node above this one.
+    fn select_from(
+        &self,
+        child_nodes: &[RcNode<G>],
+        depth: u32,
+        by_availability: bool,
+        fpu: Option<Fpu>,
+        reference: Option<&Self>,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Option<RcNode<G>> {
+        let (unvisited, visited): (Vec<_>, Vec<_>) = child_nodes
+            .iter()
+            .partition(|node| node.borrow().ni.get() == 0.);
+        let urgency = match fpu {
+            _ if visited.is_empty() => None,
+            Some(Fpu::Value(value)) => Some(value),
+            Some(Fpu::Reduction(reduction)) => {
+                let wi: Float = visited.iter().map(|node| node.borrow().wi.get()).sum();
+                let ni: Float = visited.iter().map(|node| node.borrow().ni.get()).sum();
+                let explored: f32 = visited.iter().map(|node| node.borrow().prior.get()).sum();
+                Some(wi / ni - reduction * Float::from(explored).sqrt())
+            }
+            None => None,
+        };
+        if !unvisited.is_empty() && urgency.is_none() {
+            let unvisited = unvisited.into_iter().map(|node| (node.clone(), 0.));
+            return argmax(unvisited.collect(), rng);
+        }
+
+        let visits: Float = child_nodes.iter().map(|node| node.borrow().ni.get()).sum();
+        let np = self.ni.get().max(visits);
+        let (min_reward, max_reward) = reward_range_of(child_nodes);
+        let player = self.state.player();
+        let scored = child_nodes
+            .iter()
+            .map(|node| {
+                let node_borrow = node.borrow();
+                let (wi, ni, sum_sq, minimax_value) = match urgency {
+                    Some(urgency) if node_borrow.ni.get() == 0. => {
+                        (urgency, 1., urgency * urgency, urgency)
+                    }
+                    _ => (
+                        node_borrow.value() * node_borrow.ni.get(),
+                        node_borrow.ni.get(),
+                        node_borrow.sum_sq.get(),
+                        node_borrow.minimax_value(&player),
+                    ),
+                };
+                let (ref_amaf_wi, ref_amaf_ni) = match reference {
+                    Some(reference) => node_borrow
+                        .last_action
+                        .as_ref()
+                        .and_then(|action| reference.find_child(action))
+                        .map_or((0., 0.), |node| {
+                            let node = node.borrow();
+                            (node.amaf_wi.get(), node.amaf_ni.get())
+                        }),
+                    None => (node_borrow.amaf_wi.get(), node_borrow.amaf_ni.get()),
+                };
+                let value = self.tree_policy.score(&ChildStats {
+                    wi,
+                    ni,
+                    sum_sq,
+                    np: match by_availability {
+                        true => node_borrow.availability.get(),
+                        false => np,
+                    },
+                    prior: Float::from(node_borrow.prior.get()),
+                    amaf_wi: node_borrow.amaf_wi.get(),
+                    amaf_ni: node_borrow.amaf_ni.get(),
+                    ref_amaf_wi,
+                    ref_amaf_ni,
+                    minimax_value,
+                    min_reward,
+                    max_reward,
+                    depth,
+                });
+                (node.clone(), value)
+            })
+            .collect();
+        argmax(scored, rng)
+    }
+
+    /// Select a joint action by decoupled UCT, for a state where `movers` players move at once: each of them selects
+    /// their own move by the tree policy, from the statistics of their reward summed over the joint actions with the move.
+    fn select_simultaneous(
+        &self,
+        movers: usize,
+        same_move: fn(&G, &G::Action, &G::Action, usize) -> bool,
+        depth: u32,
+        mut rng: Option<&mut dyn RngCore>,
+    ) -> Option<RcNode<G>> {
+        let child_nodes = self.child_nodes.borrow();
+        let action_of = |node: &RcNode<G>| node.borrow().last_action.clone().unwrap();
+        let np = self.ni.get();
+        let (min_reward, max_reward) = reward_range_of(&child_nodes);
+        let mut moves = vec![];
+        for i in 0..movers {
+            // A joint action with each move of the mover, and the mover's statistics of the move
+            let mut marginals: Vec<(G::Action, ChildStats)> = vec![];
+            for node in child_nodes.iter() {
+                let action = action_of(node);
+                let index = match marginals
+                    .iter()
+                    .position(|(other, _)| same_move(&self.state, other, &action, i))
+                {
+                    Some(index) => index,
+                    None => {
+                        let stats = ChildStats {
+                            wi: 0.,
+                            ni: 0.,
+                            sum_sq: 0.,
+                            np,
+                            prior: 0.,
+                            amaf_wi: 0.,
+                            amaf_ni: 0.,
+                            ref_amaf_wi: 0.,
+                            ref_amaf_ni: 0.,
+                            minimax_value: 0.,
+                            min_reward,
+                            max_reward,
+                            depth,
+                        };
+                        marginals.push((action, stats));
+                        marginals.len() - 1
+                    }
+                };
+                let node = node.borrow();
+                let stats = &mut marginals[index].1;
+                let (wi, sum_sq) = node
+                    .mover_rewards
+                    .borrow()
+                    .get(i)
+                    .copied()
+                    .unwrap_or_default();
+                stats.wi += wi;
+                stats.sum_sq += sum_sq;
+                stats.ni += node.ni.get();
+                stats.prior += Float::from(node.prior.get());
+            }
+            let unvisited: Vec<_> = marginals
+                .iter()
+                .filter(|(_, stats)| stats.ni == 0.)
+                .map(|(action, _)| (action.clone(), 0.))
+                .collect();
+            let rng = rng.as_mut().map(|rng| &mut **rng as &mut dyn RngCore);
+            let selected = match unvisited.is_empty() {
+                false => argmax(unvisited, rng),
+                true => {
+                    let scored = marginals
+                        .iter()
+                        .map(|(action, stats)| {
+                            let stats = ChildStats {
+                                minimax_value: stats.wi / stats.ni,
+                                ..*stats
+                            };
+                            (action.clone(), self.tree_policy.score(&stats))
+                        })
+                        .collect();
+                    argmax(scored, rng)
+                }
+            };
+            moves.push(selected?);
+        }
+        child_nodes
+            .iter()
+            .find(|node| {
+                let action = action_of(node);
+                moves
+                    .iter()
+                    .enumerate()
+                    .all(|(i, other)| same_move(&self.state, other, &action, i))
+            })
+            .cloned()
+    }
+
+    /// The first child with a prior that has been visited fewer times than the forced playouts of [`SearchTree::with_forced_playouts`].
+    fn forced_child(&self, k: f32) -> Option<RcNode<G>> {
+        self.child_nodes
+            .borrow()
+            .iter()
+            .find(|node| {
+                let node = node.borrow();
+                node.prior.get() > 0. && node.ni.get() < self.forced_visits(&node, k)
+            })
+            .cloned()
+    }
+
+    /// The number of visits `k * sqrt(prior * N)` forced into `child`, where `N` is the number of visits of this node.
+    fn forced_visits(&self, child: &Node<G>, k: f32) -> Float {
+        Float::from(k) * (Float::from(child.prior.get()) * self.ni.get()).sqrt()
+    }
+
+    /// The visits of the children with the forced playouts pruned, as KataGo does for its policy target. Every child but
+    /// the most visited one loses visits, at most its forced visits, as long as the tree policy still scores it
+    /// below the most visited child, keeping its mean value; a child that could be left with a single visit is pruned entirely.
+    fn pruned_visits(&self, k: f32) -> Vec<Float> {
+        let child_nodes = self.child_nodes.borrow();
+        let visits: Vec<Float> = child_nodes
+            .iter()
+            .map(|node| node.borrow().ni.get())
+            .collect();
+        let Some(best) = argmax(visits.iter().copied().enumerate().collect(), None) else {
+            return visits;
+        };
+        let np = self.ni.get().max(visits.iter().sum());
+        let (min_reward, max_reward) = reward_range_of(&child_nodes);
+        // The score of `node` if it had been visited `ni` times with the same statistics per visit
+        let score = |node: &Node<G>, ni: Float| {
+            let scale = ni / node.ni.get();
+            self.tree_policy.score(&ChildStats {
+                wi: node.wi.get() * scale,
+                ni,
+                sum_sq: node.sum_sq.get() * scale,
+                np,
+                prior: Float::from(node.prior.get()),
+                amaf_wi: node.amaf_wi.get(),
+                amaf_ni: node.amaf_ni.get(),
+                ref_amaf_wi: node.amaf_wi.get(),
+                ref_amaf_ni: node.amaf_ni.get(),
+                minimax_value: node.minimax_value(&self.state.player()),
+                min_reward,
+                max_reward,
+                depth: 0,
+            })
+        };
+        let best_score = score(&child_nodes[best].borrow(), visits[best]);
+        visits
+            .iter()
+            .enumerate()
+            .map(|(i, &ni)| {
+                if i == best || ni == 0. {
+                    return ni;
+                }
+                let node = child_nodes[i].borrow();
+                let floor = ni - self.forced_visits(&node, k);
+                let mut pruned = ni;
+                while pruned > 1. && pruned - 1. >= floor && score(&node, pruned - 1.) < best_score
+                {
+                    pruned -= 1.;
+                }
+                match pruned <= 1. && floor < 1. {
+                    true => 0.,
+                    false => pruned,
+                }
+            })
+            .collect()
+    }
+
+    /// Select a child among the actions available in `state`, a determinization of this node's information set,
+    /// creating the children that are missing.
+    fn select_determinized(
+        &self,
+        state: &G,
+        depth: u32,
+        reference: Option<&Self>,
+        ctx: &mut SearchContext<G>,
+    ) -> Option<RcNode<G>> {
+        let (actions, priors) = match state.transition_kind() {
+            TransitionKind::Decision => {
+                let actions = actions_of(state);
+                let priors = priors_of(state, &actions, ctx.priors);
+                (actions, priors)
+            }
+            TransitionKind::Chance => {
+                let mut outcomes = state.chance_outcomes();
+                let indices = outcomes.iter().enumerate().map(|(i, (_, p))| (i, *p));
+                let index = sample_weighted(indices.collect(), ctx.rng)?;
+                let (action, probability) = outcomes.swap_remove(index);
+                (vec![action], vec![probability])
+            }
+        };
+        let mut available = vec![];
+        for (action, prior) in actions.into_iter().zip(priors) {
+            let node = match self.find_child(&action) {
+                Some(node) => node,
+                None if ctx.at_capacity() => continue,
+                None => {
+                    let node = self.derive_child(state, action, prior);
+                    self.push_child(node.clone());
+                    // The child shares the subtree of the information set its key stands for, whichever world reached it
+                    if let Some(transpositions) = ctx.transpositions {
+                        transpositions.share(&node);
+                    }
+                    ctx.nodes.set(ctx.nodes.get() + 1);
+                    node
+                }
+            };
+            let availability = node.borrow().availability.get();
+            node.borrow().availability.set(availability + 1.);
+            available.push(node);
+        }
+        let fpu = ctx.fpu;
+        self.select_from(&available, depth, true, fpu, reference, ctx.tiebreak_rng())
+    }
+
+    fn sample_chance_outcome(&self, rng: &mut dyn RngCore) -> Option<RcNode<G>> {
+        let weighted = self
+            .child_nodes
+            .borrow()
+            .iter()
+            .map(|node| (node.clone(), node.borrow().prior.get()))
+            .collect();
+        sample_weighted(weighted, rng)
+    }
+
+    fn select_most_visited(
+        &self,
+        solver: bool,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Option<RcNode<G>> {
+        self.select_final(FinalSelection::MaxVisits, solver, rng)
+    }
+
+    /// Choose the child to play by `selection`, see [`FinalSelection`].
+    fn select_final(
+        &self,
+        selection: FinalSelection,
+        solver: bool,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Option<RcNode<G>> {
+        let child_nodes = self.child_nodes.borrow();
+        let mut child_nodes = match solver {
+            true => self.solver_choices(&child_nodes),
+            false => child_nodes.clone(),
+        };
+        if selection == FinalSelection::Robust {
+            let max_visits = child_nodes
+                .iter()
+                .map(|node| node.borrow().ni.get())
+                .fold(0., Float::max);
+            child_nodes.retain(|node| node.borrow().ni.get() == max_visits);
+        }
+        let scored = child_nodes
+            .into_iter()
+            .map(|node| {
+                let node_borrow = node.borrow();
+                let (ni, mean) = (node_borrow.ni.get(), node_borrow.mean_value());
+                let score = match selection {
+                    FinalSelection::MaxVisits => ni,
+                    // Children that have never been visited have no value to compare
+                    _ if ni == 0. => Float::MIN,
+                    FinalSelection::MaxValue | FinalSelection::Robust => mean,
+                    FinalSelection::Secure(a) => mean + a / ni.sqrt(),
+                    FinalSelection::MaxReward => node_borrow.max_reward().unwrap_or(Float::MIN),
+                };
+                drop(node_borrow);
+                (node, score)
+            })
+            .collect();
+        argmax(scored, rng)
+    }
+
+    /// Expand all possible actions. The children get their priors from `priors`, normalized over the possible actions,
+    /// or a uniform prior if it is not given.
+    /// The children of a chance state are its chance outcomes instead, with their probabilities as priors.
+    /// With `transpositions`, children reaching a known position share its subtree.
+    /// With `widening`, the decisions are sorted by their priors and only revealed as the node is visited, see [`Node::widen`].
+    fn expand(
+        &self,
+        priors: Option<&PriorsFn<'_, G, G::Action>>,
+        transpositions: Option<&dyn Transpositions<G>>,
+        widening: Option<(f32, f32)>,
+    ) {
+        if self.is_expanded() {
+            if let Some(widening) = widening {
+                self.widen(transpositions, widening);
+            }
+            return;
+        }
+        let children = match self.state.transition_kind() {
+            TransitionKind::Chance => self.state.chance_outcomes(),
+            TransitionKind::Decision => {
+                let actions = actions_of(self.state.as_ref());
+                let priors = priors_of(self.state.as_ref(), &actions, priors);
+                let mut children: Vec<_> = actions.into_iter().zip(priors).collect();
+                if let Some(widening) = widening {
+                    // Stable, so that actions with equal priors keep the order of `possible_actions`
+                    children.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+                    children.reverse();
+                    *self.pending.borrow_mut() = children;
+                    self.widen(transpositions, widening);
+                    return;
+                }
+                children
+            }
+        };
+        for (action, prior) in children {
+            self.add_child(action, prior, transpositions);
+        }
+    }
+
+    /// Progressive widening: reveal the pending decisions with the highest priors until the node has `ceil(k * ni^alpha)`
+    /// children, counting an unvisited node as visited once.
+    fn widen(&self, transpositions: Option<&dyn Transpositions<G>>, (k, alpha): (f32, f32)) {
+        let visits = self.ni.get().max(1.);
+        let limit = (Float::from(k) * visits.powf(Float::from(alpha))).ceil() as usize;
+        while self.child_nodes.borrow().len() < limit {
+            let Some((action, prior)) = self.pending.borrow_mut().pop() else {
+                break;
+            };
+            self.add_child(action, prior, transpositions);
+        }
+    }
+
+    /// Expand this node with all of its children, revealing its pending decisions, and its descendants down to `depth`
+    /// plies below it, without expanding terminal nodes or going on once `nodes` reaches `max_nodes`.
+    /// `nodes` counts the nodes added.
+    fn expand_to_depth(
+        &self,
+        depth: usize,
+        priors: Option<&PriorsFn<'_, G, G::Action>>,
+        transpositions: Option<&dyn Transpositions<G>>,
+        nodes: &Cell<usize>,
+        max_nodes: Option<usize>,
+    ) {
+        if depth == 0
+            || self.end_status().is_some()
+            || max_nodes.is_some_and(|max_nodes| nodes.get() >= max_nodes)
+        {
+            return;
+        }
+        let children = self.child_nodes.borrow().len();
+        self.expand(priors, transpositions, None);
+        loop {
+            let Some((action, prior)) = self.pending.borrow_mut().pop() else {
+                break;
+            };
+            self.add_child(action, prior, transpositions);
+        }
+        nodes.set(nodes.get() + self.child_nodes.borrow().len() - children);
+        let child_nodes = self.child_nodes.borrow().clone();
+        for child_node in child_nodes {
+            child_node.borrow().expand_to_depth(
+                depth - 1,
+                priors,
+                transpositions,
+                nodes,
+                max_nodes,
+            );
+        }
+    }
+
+    /// Reveal the pending decision `action` right away, e.g. when the game moves on with it.
+    fn reveal(
+        &self,
+        action: &G::Action,
+        transpositions: Option<&dyn Transpositions<G>>,
+    ) -> Option<RcNode<G>> {
+        let mut pending = self.pending.borrow_mut();
+        let index = pending.iter().position(|(a, _)| a == action)?;
+        let (action, prior) = pending.remove(index);
+        drop(pending);
+        Some(self.add_child(action, prior, transpositions))
+    }
+
+    fn add_child(
+        &self,
+        action: G::Action,
+        prior: f32,
+        transpositions: Option<&dyn Transpositions<G>>,
+    ) -> RcNode<G> {
+        let node = self.derive_child(&self.state, action, prior);
+        if let Some(transpositions) = transpositions {
+            transpositions.share(&node);
+        }
+        self.push_child(node.clone());
+        node
+    }
+
+    fn is_expanded(&self) -> bool {
+        !self.child_nodes.borrow().is_empty()
+    }
+
+    /// Update the AMAF statistics of the children whose action was taken by the player to move at this node
+    /// at any point of `moves`, the decisions taken after this node.
+    fn update_amaf(
+        &self,
+        moves: &[(G::Player, G::Action)],
+        same_player: fn(&G::Player, &G::Player) -> bool,
+        outcome: &G::EndStatus,
+    ) {
+        if self.state.transition_kind() == TransitionKind::Chance {
+            return;
+        }
+        let player = self.state.player();
+        let reward = player.reward_when_outcome_is(outcome);
+        for node in self.child_nodes.borrow().iter() {
+            let node = node.borrow();
+            let Some(action) = &node.last_action else {
+                continue;
+            };
+            if moves
+                .iter()
+                .any(|(p, a)| a == action && same_player(p, &player))
+            {
+                node.amaf_ni.set(node.amaf_ni.get() + 1.);
+                node.amaf_wi.set(node.amaf_wi.get() + Float::from(reward));
+            }
+        }
+    }
+
+    fn backpropagate(&self, reward: Float) {
+        self.ni.set(self.ni.get() + 1.);
+        self.wi.set(self.wi.get() + reward);
+        self.sum_sq.set(self.sum_sq.get() + reward * reward);
+        let range = match self.reward_range.get() {
+            Some((min, max)) => (min.min(reward), max.max(reward)),
+            None => (reward, reward),
+        };
+        self.reward_range.set(Some(range));
+    }
+
+    /// Back up the value of the node from the values of its children, by their power mean with exponent `p` weighted by
+    /// their visits, or by their weighted mean at a chance state. The children's values are those of the player to move
+    /// at the node; unless it also moved at the parent, the node's value is its mean value moved by the opposite of the
+    /// gain of the power mean over the mean, as if the game were zero-sum.
+    fn back_up_power_mean(&self, p: Float, same_player: bool) {
+        let p = match self.state.transition_kind() {
+            TransitionKind::Decision => p,
+            TransitionKind::Chance => 1.,
+        };
+        let (mut powered, mut wi, mut ni) = (0., 0., 0.);
+        for child in self.child_nodes.borrow().iter() {
+            let child = child.borrow();
+            let visits = child.ni.get();
+            if visits > 0. {
+                powered += visits * child.value().max(0.).powf(p);
+                wi += child.wi.get();
+                ni += visits;
+            }
+        }
+        if ni == 0. {
+            return;
+        }
+        let power_mean = (powered / ni).powf(1. / p);
+        self.backed_up.set(Some(match same_player {
+            true => power_mean,
+            false => self.mean_value() - (power_mean - wi / ni),
+        }));
+    }
+
+    /// Back up the implicit minimax outcome of the node: a leaf reached for the first time takes its end status, or the
+    /// evaluation of its state by `evaluator`, and a decision state takes the outcome of the child best for the player
+    /// to move among those that have one. A chance state keeps the evaluation of its own state.
+    fn back_up_minimax(&self, evaluator: &DynEvaluator<'_, G>, leaf: bool) {
+        if leaf {
+            if self.minimax_outcome.borrow().is_none() {
+                let outcome = match self.end_status() {
+                    Some(outcome) => outcome.clone(),
+                    None => evaluator.evaluate(&self.state),
+                };
+                *self.minimax_outcome.borrow_mut() = Some(outcome);
+            }
+            return;
+        }
+        if let TransitionKind::Chance = self.state.transition_kind() {
+            return;
+        }
+        let player = self.state.player();
+        let mut best: Option<(f32, G::EndStatus)> = None;
+        for child in self.child_nodes.borrow().iter() {
+            let child = child.borrow();
+            let Some(outcome) = &*child.minimax_outcome.borrow() else {
+                continue;
+            };
+            let reward = player.reward_when_outcome_is(outcome);
+            if best.as_ref().is_none_or(|(best, _)| reward > *best) {
+                best = Some((reward, outcome.clone()));
+            }
+        }
+        if let Some((_, outcome)) = best {
+            *self.minimax_outcome.borrow_mut() = Some(outcome);
+        }
+    }
+
+    /// The implicit minimax value of the node for `player`, or its mean value if it has no minimax outcome
+    fn minimax_value(&self, player: &G::Player) -> Float {
+        match &*self.minimax_outcome.borrow() {
+            Some(outcome) => Float::from(player.reward_when_outcome_is(outcome)),
+            None => self.mean_value(),
+        }
+    }
+
+    /// The value of the node for the player to move at the parent: the value backed up by the power mean
+    /// if there is one, see [`Backup::PowerMean`], or the mean value otherwise
+    fn value(&self) -> Float {
+        self.backed_up.get().unwrap_or_else(|| self.mean_value())
+    }
+
+    /// Add the rewards of the players moving simultaneously at the parent, discounted by `discount`, to their statistics.
+    fn backpropagate_movers(&self, movers: &[G::Player], discount: f32, outcome: &G::EndStatus) {
+        let mut mover_rewards = self.mover_rewards.borrow_mut();
+        mover_rewards.resize(movers.len(), (0., 0.));
+        for ((wi, sum_sq), mover) in mover_rewards.iter_mut().zip(movers) {
+            let reward = Float::from(discount * mover.reward_when_outcome_is(outcome));
+            *wi += reward;
+            *sum_sq += reward * reward;
+        }
+    }
+
+    /// Simulate a game play from `root_node`, whose state is `state`, or a determinization of it in ISMCTS.
+    /// The tree is descended in a loop rather than by recursion, so that long games can't overflow the stack:
+    /// the visited nodes are pushed to a path until a terminal state or a new leaf is reached,
+    /// and the outcome is then backpropagated along the path from the leaf up to the root.
+    fn simulate(root_node: &RcNode<G>, state: Rc<G>, ctx: &mut SearchContext<G>) -> G::EndStatus {
+        let (descent, outcome) = Node::descend(root_node, state, ctx);
+        let outcome = outcome.expect("only deferred leaves end a descent without an outcome");
+        descent.backpropagate(&outcome, ctx);
+        outcome
+    }
+
+    /// Descend the tree from `root_node` like [`Node::simulate`], and return the path with the outcome it reached.
+    /// The outcome is `None` if the descent stopped at a leaf whose evaluation is deferred.
+    fn descend(
+        root_node: &RcNode<G>,
+        state: Rc<G>,
+        ctx: &mut SearchContext<G>,
+    ) -> (Descent<G>, Option<G::EndStatus>) {
+        let mut path = vec![(root_node.clone(), state.player())];
+        let mut player_indices = vec![state.current_player_index()];
+        let mut step_rewards = vec![0.];
+        let mut moves = vec![];
+        let mut first_moves = vec![0];
+        let mut reference = root_node.clone();
+        let mut state = state;
+        let record_moves =
+            ctx.amaf.is_some() || ctx.leaf_evaluation.learns() || ctx.best_solution.is_some();
+        let mut ended = false;
+        let outcome = loop {
+            let node = path.last().unwrap().0.clone();
+            let node = node.borrow();
+            // A determinization may differ from the node's state, so its end status can't be cached
+            let outcome = match ctx.ismcts {
+                true => end_status_of(state.as_ref()),
+                false => node.end_status().cloned(),
+            };
+            if let Some(outcome) = outcome {
+                ended = true;
+                break Some(outcome);
+            }
+            // A solved node is as good as terminal: its outcome under optimal play is known
+            if let Some(outcome) = node.solved.get() {
+                break Some(outcome.clone());
+            }
+            // A deferred leaf is only expanded once it has been evaluated
+            if ctx.defer_leaves && !node.is_expanded() {
+                break None;
+            }
+            // The root of a search through one of its children descends into it, even before the root has been visited
+            let new_leaf = node.ni.get() == 0. && !(path.len() == 1 && ctx.root_child.is_some());
+            match ctx.leaf_evaluation {
+                LeafEvaluation::Rollout(rollout_policy) if new_leaf => {
+                    let moves = record_moves.then_some(&mut moves);
+                    // Only a rollout that is neither cut off nor limited is sure to reach the end
+                    ended = ctx.rollout_cutoff.is_none() && ctx.max_rollout_steps.is_none();
+                    break Some(play_rollout(
+                        &**rollout_policy,
+                        &state,
+                        ctx.rng,
+                        ctx.rollout_cutoff,
+                        ctx.max_rollout_steps,
+                        moves,
+                    ));
+                }
+                LeafEvaluation::Evaluate(evaluator) if new_leaf => {
+                    break Some(evaluator.evaluate(&state));
+                }
+                _ => {}
+            }
+
+            // The deepest node of the path visited often enough for its AMAF statistics to be used below it, see `grave`
+            if node
+                .tree_policy
+                .reference_visits()
+                .is_some_and(|visits| node.ni.get() >= visits)
+            {
+                reference = path.last().unwrap().0.clone();
+            }
+            let reference_node = reference.borrow();
+            let reference_node = match Rc::ptr_eq(&reference, &path.last().unwrap().0) {
+                true => None,
+                false => Some(&*reference_node),
+            };
+
+            let depth = path.len() as u32 - 1;
+            let selected = if ctx.ismcts {
+                node.select_determinized(&state, depth, reference_node, ctx)
+                    .map(|selected_node| {
+                        let action = selected_node.borrow().last_action.clone().unwrap();
+                        (selected_node, Rc::new(state.act(&action)))
+                    })
+            } else {
+                if !ctx.at_capacity() {
+                    let children = node.child_nodes.borrow().len();
+                    node.expand(ctx.priors, ctx.transpositions, ctx.widening);
+                    let added = node.child_nodes.borrow().len() - children;
+                    ctx.nodes.set(ctx.nodes.get() + added);
+                    #[cfg(feature = "tracing")]
+                    if added > 0 {
+                        tracing::trace!(depth, children = added, "expanded");
+                    }
+                }
+                let selected_node = match node.state.transition_kind() {
+                    _ if path.len() == 1 && ctx.root_child.is_some() => ctx.root_child.cloned(),
+                    TransitionKind::Decision => {
+                        let forced = match ctx.forced_playouts {
+                            Some(k) if path.len() == 1 => node.forced_child(k),
+                            _ => None,
+                        };
+                        let movers = match ctx.simultaneous {
+                            Some((movers, _)) => movers(&node.state).len(),
+                            None => 1,
+                        };
+                        forced.or_else(|| match ctx.simultaneous {
+                            Some((_, same_move)) if movers > 1 => node.select_simultaneous(
+                                movers,
+                                same_move,
+                                depth,
+                                ctx.tiebreak_rng(),
+                            ),
+                            _ => node.select(
+                                depth,
+                                ctx.solver,
+                                ctx.fpu,
+                                reference_node,
+                                ctx.tiebreak_rng(),
+                            ),
+                        })
+                    }
+                    TransitionKind::Chance => node.sample_chance_outcome(ctx.rng),
+                };
+                selected_node.map(|selected_node| {
+                    let next_state = selected_node.borrow().state.clone();
+                    (selected_node, next_state)
+                })
+            };
+            let (selected_node, next_state) = match selected {
+                Some(selected) => selected,
+                // A leaf that can't be expanded any more is evaluated like a new leaf, by a random rollout if nothing else
+                None if ctx.at_capacity() => {
+                    break Some(match ctx.leaf_evaluation {
+                        LeafEvaluation::Rollout(rollout_policy) => play_rollout(
+                            &**rollout_policy,
+                            &state,
+                            ctx.rng,
+                            ctx.rollout_cutoff,
+                            ctx.max_rollout_steps,
+                            None,
+                        ),
+                        LeafEvaluation::Evaluate(evaluator) => evaluator.evaluate(&state),
+                        LeafEvaluation::Expand => play_rollout(
+                            &RandomRollout,
+                            state.as_ref(),
+                            ctx.rng,
+                            ctx.rollout_cutoff,
+                            ctx.max_rollout_steps,
+                            None,
+                        ),
+                    });
+                }
+                None => panic!("{}", NO_POSSIBLE_ACTIONS),
+            };
+            #[cfg(feature = "tracing")]
+            {
+                let selected = selected_node.borrow();
+                tracing::trace!(
+                    depth,
+                    child = node
+                        .child_nodes
+                        .borrow()
+                        .iter()
+                        .position(|child| Rc::ptr_eq(child, &selected_node)),
+                    wi = selected.wi.get(),
+                    ni = selected.ni.get(),
+                    "selected"
+                );
+            }
+            if record_moves && state.transition_kind() == TransitionKind::Decision {
+                let action = selected_node.borrow().last_action.clone().unwrap();
+                moves.push((state.player(), action));
+            }
+            first_moves.push(moves.len());
+            if let Some((step_reward, _)) = ctx.step_rewards {
+                let action = selected_node.borrow().last_action.clone().unwrap();
+                step_rewards.push(step_reward(&state, &action));
+            }
+            path.push((selected_node, state.player()));
+            player_indices.push(state.current_player_index());
+            state = next_state;
+        };
+        let descent = Descent {
+            path,
+            player_indices,
+            step_rewards,
+            moves,
+            first_moves,
+            state,
+            ended,
+        };
+        (descent, outcome)
+    }
+
+    pub fn state(&self) -> Rc<G> {
+        self.state.clone()
+    }
+
+    /// The node this one is a child of, or `None` for the root of the search tree. With the transposition table,
+    /// the children shared by the nodes of a position link to the node that created them.
+    pub fn parent(&self) -> Option<RcNode<G>> {
+        self.parent.borrow().upgrade()
+    }
+
+    pub fn child_nodes(&self) -> Vec<RcNode<G>> {
+        self.child_nodes.borrow().clone()
+    }
+
+    /// The child reached by `action`, or `None` if it hasn't been expanded.
+    pub fn child(&self, action: &G::Action) -> Option<RcNode<G>> {
+        self.find_child(action)
+    }
+
+    pub fn wi(&self) -> Float {
+        self.wi.get()
+    }
+
+    pub fn ni(&self) -> Float {
+        self.ni.get()
+    }
+
+    /// The largest reward of a single simulation through this node, or `None` if it hasn't been visited. Following
+    /// the children with the largest one from the root retraces the best outcome found, e.g. in a single-player game.
+    pub fn max_reward(&self) -> Option<Float> {
+        self.reward_range.get().map(|(_, max)| max)
+    }
+
+    /// The prior probability of this node's action, as normalized by [`SearchTree::with_priors`].
+    pub fn prior(&self) -> f32 {
+        self.prior.get()
+    }
+
+    /// The number of nodes in the subtree of `node`, itself included, counting the children shared through the
+    /// transposition table once.
+    fn count(node: &RcNode<G>) -> usize {
+        let mut count = 0;
+        let mut visited = HashSet::new();
+        let mut nodes = vec![node.clone()];
+        while let Some(node) = nodes.pop() {
+            count += 1;
+            let node = node.borrow();
+            if visited.insert(Rc::as_ptr(&node.child_nodes)) {
+                nodes.extend(node.child_nodes.borrow().iter().cloned());
+            }
+        }
+        count
+    }
+
+    /// Copy the node and its statistics without its children, as a child of `parent`.
+    fn copy(&self, parent: WeakNode<G>) -> RcNode<G> {
+        Node {
+            state: self.state.clone(),
+            last_action: self.last_action.clone(),
+            child_nodes: Rc::default(),
+            #[cfg(feature = "action-index")]
+            hash_action: self.hash_action,
+            #[cfg(feature = "action-index")]
+            child_index: Rc::new(RefCell::new(self.child_index.borrow().clone())),
+            end_status: self.end_status.clone(),
+            solved: self.solved.clone(),
+            wi: self.wi.clone(),
+            ni: self.ni.clone(),
+            sum_sq: self.sum_sq.clone(),
+            prior: self.prior.clone(),
+            pending: self.pending.clone(),
+            availability: self.availability.clone(),
+            amaf_wi: self.amaf_wi.clone(),
+            amaf_ni: self.amaf_ni.clone(),
+            reward_range: self.reward_range.clone(),
+            mover_rewards: self.mover_rewards.clone(),
+            backed_up: self.backed_up.clone(),
+            minimax_outcome: self.minimax_outcome.clone(),
+            tree_policy: self.tree_policy.clone(),
+            this: Weak::new(),
+            parent: RefCell::new(parent),
+        }
+        .into_rc()
+    }
+
+    /// Copy the subtree of `node`, sharing the states and the tree policy, and return the copies of its nodes,
+    /// `node` included, by the address of the node they copy.
+    /// Children shared by the nodes of a position are still shared by the copies.
+    fn deep_copy(node: &RcNode<G>) -> NodeCopies<G> {
+        let root_copy = node.borrow().copy(Weak::new());
+        let mut copies = NodeCopies::new();
+        copies.insert(Rc::as_ptr(node), root_copy.clone());
+        // The copy owning the copy of each list of children, by the address of the list
+        let mut owners: HashMap<_, RcNode<G>> = HashMap::new();
+        let mut nodes = vec![(node.clone(), root_copy.clone())];
+        while let Some((node, copy_rc)) = nodes.pop() {
+            let node = node.borrow();
+            let mut copy = copy_rc.borrow_mut();
+            let list = Rc::as_ptr(&node.child_nodes);
+            if let Some(owner) = owners.get(&list) {
+                let owner = owner.borrow();
+                copy.child_nodes = owner.child_nodes.clone();
+                #[cfg(feature = "action-index")]
+                {
+                    copy.child_index = owner.child_index.clone();
+                }
+                continue;
+            }
+            for child in node.child_nodes.borrow().iter() {
+                let child_copy = child.borrow().copy(copy.this.clone());
+                copies.insert(Rc::as_ptr(child), child_copy.clone());
+                copy.child_nodes.borrow_mut().push(child_copy.clone());
+                nodes.push((child.clone(), child_copy));
+            }
+            drop(copy);
+            owners.insert(list, copy_rc);
+        }
+        copies
+    }
+
+    /// Add the statistics of `other`, the node of the same position in another tree, to those of the node.
+    /// The values backed up from the children, and the solved outcome, are only taken from `other` if the node has none.
+    fn add_statistics(&self, other: &Self) {
+        self.wi.set(self.wi.get() + other.wi.get());
+        self.ni.set(self.ni.get() + other.ni.get());
+        self.sum_sq.set(self.sum_sq.get() + other.sum_sq.get());
+        self.availability
+            .set(self.availability.get() + other.availability.get());
+        self.amaf_wi.set(self.amaf_wi.get() + other.amaf_wi.get());
+        self.amaf_ni.set(self.amaf_ni.get() + other.amaf_ni.get());
+        let range = match (self.reward_range.get(), other.reward_range.get()) {
+            (Some((min, max)), Some((other_min, other_max))) => {
+                Some((min.min(other_min), max.max(other_max)))
+            }
+            (range, None) | (None, range) => range,
+        };
+        self.reward_range.set(range);
+        let mut mover_rewards = self.mover_rewards.borrow_mut();
+        let other_rewards = other.mover_rewards.borrow();
+        if mover_rewards.len() < other_rewards.len() {
+            mover_rewards.resize(other_rewards.len(), (0., 0.));
+        }
+        for ((wi, sum_sq), (other_wi, other_sum_sq)) in
+            mover_rewards.iter_mut().zip(other_rewards.iter())
+        {
+            *wi += other_wi;
+            *sum_sq += other_sum_sq;
+        }
+        if self.backed_up.get().is_none() {
+            self.backed_up.set(other.backed_up.get());
+        }
+        if self.minimax_outcome.borrow().is_none() {
+            *self.minimax_outcome.borrow_mut() = other.minimax_outcome.borrow().clone();
+        }
+        if let (None, Some(outcome)) = (self.solved.get(), other.solved.get()) {
+            let _ = self.solved.set(outcome.clone());
+        }
+    }
+
+    /// Zero the rewards, visits, availability and AMAF statistics of the node.
+    fn zero_statistics(&self) {
+        self.wi.set(0.);
+        self.ni.set(0.);
+        self.sum_sq.set(0.);
+        self.availability.set(0.);
+        self.amaf_wi.set(0.);
+        self.amaf_ni.set(0.);
+        self.reward_range.set(None);
+        self.mover_rewards.borrow_mut().clear();
+        self.backed_up.set(None);
+        self.minimax_outcome.take();
+    }
+
+    /// Drop the subtree below the node, keeping the node itself and its statistics as a leaf,
+    /// so that it is expanded again the next time it is selected.
+    /// Children shared with other nodes of the same position are left to them.
+    fn collapse(&mut self) {
+        self.child_nodes = Rc::default();
+        #[cfg(feature = "action-index")]
+        {
+            self.child_index = Rc::default();
+        }
+        self.pending.get_mut().clear();
+    }
+
+    /// The outcome of the game under optimal play from this node, if the solver of [`SearchTree::with_solver`] proved it
+    pub fn solved(&self) -> Option<G::EndStatus> {
+        self.solved.get().cloned()
+    }
+
+    /// The mean reward `wi / ni`, or 0 if the node has not been visited
+    pub fn mean_value(&self) -> Float {
+        match self.ni.get() {
+            ni if ni > 0. => self.wi.get() / ni,
+            _ => 0.,
+        }
+    }
+}
+
+/// The result of [`SearchTree::search_stats`]: the chosen action and the statistics behind the choice.
+#[derive(Debug, Clone)]
+pub struct SearchResult<A> {
+    /// The chosen action
+    pub action: A,
+    /// Times the chosen action has been selected
+    pub visits: Float,
+    /// Mean reward of the chosen action
+    pub mean_value: Float,
+    /// Number of simulations run by this call, not counting those of earlier searches of the same tree,
+    /// see [`SearchTree::simulations_done`] for the total
+    pub simulations_run: u32,
+    /// The action, times of selection, and mean reward of each child of the root, in the order of [`GameState::order_actions`]
+    pub children: Vec<(A, Float, Float)>,
+}
+
+/// How [`SearchTree::search`] chooses the action to play among the children of the root, see [`SearchTree::with_final_selection`].
+/// Ties are broken like the selection during the search, see [`SearchTree::with_random_tiebreak`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FinalSelection {
+    /// The child with the most visits, the "max child"
+    #[default]
+    MaxVisits,
+    /// The child with the highest mean value `wi / ni`
+    MaxValue,
+    /// The child with the most visits, breaking ties between them by their mean value, the "robust child"
+    Robust,
+    /// The child with the highest `wi / ni + a / sqrt(ni)`, the "secure child"; a negative `a` makes it a lower
+    /// confidence bound, which favours the children whose value is backed by many visits
+    Secure(Float),
+    /// The child through which the simulation with the largest reward went, i.e. the one leading to the best outcome
+    /// found rather than the best on average, for single-player optimization with policies like [`sp_mcts`]
+    MaxReward,
+}
+
+/// How the value of a node is backed up from the simulations through it, see [`SearchTree::with_backup`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Backup {
+    /// The mean reward `wi / ni` of the simulations through the node
+    #[default]
+    Mean,
+    /// The power mean `(Σ n_c / n * v_c^p)^(1/p)` of the values `v_c` of the children, weighted by their visits `n_c`,
+    /// as in Power-UCT: `p = 1` is the mean, and the larger `p` is, the closer it gets to the value of the best child
+    PowerMean(Float),
+}
+
+/// What to do at the root according to [`SearchTree::decide_with_resign`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision<A> {
+    /// Take the action
+    Move(A),
+    /// Resign, since the game is lost or hopeless
+    Resign,
+}
+
+/// The statistics of every node of a search tree, taken by [`SearchTree::snapshot_statistics`]
+/// to be restored by [`SearchTree::restore_statistics`].
+#[derive(Debug, Clone)]
+pub struct StatsSnapshot {
+    /// in the order of a depth-first walk from the root, which visits the children shared by transpositions once
+    nodes: Vec<NodeStatistics>,
+    simulations: u32,
+}
+
+/// The statistics of a node in a [`StatsSnapshot`].
+#[derive(Debug, Clone)]
+struct NodeStatistics {
+    wi: Float,
+    ni: Float,
+    sum_sq: Float,
+    availability: Float,
+    amaf_wi: Float,
+    amaf_ni: Float,
+    reward_range: Option<(Float, Float)>,
+    mover_rewards: Vec<(Float, Float)>,
+    backed_up: Option<Float>,
+    /// the number of children, or `None` if they are shared with a node visited before
+    children: Option<usize>,
+}
+
+/// The progress of [`SearchTree::search_with_callback`], reported while it is running.
+#[derive(Debug, Clone)]
+pub struct SearchProgress<A> {
+    /// Number of simulations run by this search so far
+    pub iterations: u32,
+    /// The most visited action of the root so far, or `None` if the root has not been expanded yet
+    pub best_action: Option<A>,
+    /// Mean reward of the best action
+    pub mean_value: Float,
+    /// Length of the principal variation, see [`SearchTree::principal_variation`]
+    pub depth: usize,
+    /// Number of nodes in the tree, see [`SearchTree::node_count`]
+    pub nodes: usize,
+}
+
+/// [`SearchTree`] is the main struct to use for Monte Carlo Tree Search.
+/// Pass a [`GameState`] wrapped in a `RC` to [`SearchTree::new`] to create a new search tree.
+/// Then you can call [`SearchTree::search`] to get the best action and [`SearchTree::renew`] to move to the next state.
+pub struct SearchTree<G: GameState> {
+    root_node: RcNode<G>,
+    leaf_evaluation: LeafEvaluation<G>,
+    rollout_cutoff: Option<SharedRolloutCutoff<G>>,
+    max_rollout_steps: Option<(u32, G::EndStatus)>,
+    implicit_minimax: Option<Rc<DynEvaluator<'static, G>>>,
+    priors: Option<Rc<PriorsFn<'static, G, G::Action>>>,
+    transpositions: Option<Rc<dyn Transpositions<G>>>,
+    ismcts: bool,
+    solver: bool,
+    widening: Option<(f32, f32)>,
+    fpu: Option<Fpu>,
+    forced_playouts: Option<f32>,
+    amaf: Option<SamePlayer<G>>,
+    discount: f32,
+    backup: Option<PowerMeanBackup<G::Player>>,
+    step_rewards: Option<StepRewards<G::Player, G, G::Action>>,
+    simultaneous: Option<Simultaneous<G::Player, G, G::Action>>,
+    final_selection: FinalSelection,
+    /// the concentration and the weight of the Dirichlet noise mixed into the priors of the root's children,
+    /// and whether it has been mixed into those of the current root
+    root_noise: Option<(f32, f32)>,
+    root_noised: Cell<bool>,
+    exploration_schedule: Option<ExplorationSchedule>,
+    #[cfg(feature = "std")]
+    stats_handle: OnceCell<StatsHandle<G::Action>>,
+    best_solution: Option<RefCell<Option<Solution<G>>>>,
+    /// the simulations done when the running search began, and the simulations it runs in total,
+    /// if it runs them in several parts
+    search_progress: Cell<Option<(u32, u32)>>,
+    simulations: Cell<u32>,
+    nodes: Cell<usize>,
+    max_nodes: Option<usize>,
+    rng: RefCell<Box<dyn RngCore>>,
+    random_tiebreak: bool,
+    #[cfg(debug_assertions)]
+    reward_sum: Cell<Option<f32>>,
+}
+
+/// A deep copy of the tree: its nodes and their statistics are copied, so that the copy can be searched and renewed
+/// without affecting the original, e.g. to explore several continuations of an analyzed position without searching it
+/// again. The game states, the policies and the callbacks are shared with the original, and so are the children of
+/// transpositions among the copies. The RNG of the copy is a `StdRng` seeded from the RNG of the original, which advances it.
+impl<G: GameState> Clone for SearchTree<G> {
+    fn clone(&self) -> Self {
+        let copies = Node::deep_copy(&self.root_node);
+        let root_node = copies[&Rc::as_ptr(&self.root_node)].clone();
+        let seed = self.rng.borrow_mut().next_u64();
+        SearchTree {
+            root_node,
+            leaf_evaluation: self.leaf_evaluation.clone(),
+            rollout_cutoff: self.rollout_cutoff.clone(),
+            max_rollout_steps: self.max_rollout_steps.clone(),
+            implicit_minimax: self.implicit_minimax.clone(),
+            priors: self.priors.clone(),
+            transpositions: self
+                .transpositions
+                .as_ref()
+                .map(|transpositions| transpositions.copy(&copies)),
+            ismcts: self.ismcts,
+            solver: self.solver,
+            widening: self.widening,
+            fpu: self.fpu,
+            forced_playouts: self.forced_playouts,
+            amaf: self.amaf,
+            discount: self.discount,
+            backup: self.backup,
+            step_rewards: self.step_rewards,
+            simultaneous: self.simultaneous,
+            final_selection: self.final_selection,
+            root_noise: self.root_noise,
+            root_noised: self.root_noised.clone(),
+            best_solution: self.best_solution.clone(),
+            exploration_schedule: self.exploration_schedule.clone(),
+            #[cfg(feature = "std")]
+            stats_handle: OnceCell::new(),
+            search_progress: Cell::new(None),
+            simulations: self.simulations.clone(),
+            nodes: self.nodes.clone(),
+            max_nodes: self.max_nodes,
+            rng: RefCell::new(Box::new(StdRng::seed_from_u64(seed))),
+            random_tiebreak: self.random_tiebreak,
+            #[cfg(debug_assertions)]
+            reward_sum: self.reward_sum.clone(),
+        }
+    }
+}
+
+impl<G: GameState> SearchTree<G> {
+    /// Create a new search tree.
+    pub fn new(game_state: Rc<G>) -> Self {
+        SearchTree {
+            root_node: Node::new(game_state, Rc::new(uct)).into_rc(),
+            leaf_evaluation: LeafEvaluation::Expand,
+            rollout_cutoff: None,
+            max_rollout_steps: None,
+            implicit_minimax: None,
+            priors: None,
+            transpositions: None,
+            ismcts: false,
+            solver: false,
+            widening: None,
+            fpu: None,
+            forced_playouts: None,
+            amaf: None,
+            discount: 1.,
+            backup: None,
+            step_rewards: None,
+            simultaneous: None,
+            final_selection: FinalSelection::MaxVisits,
+            root_noise: None,
+            root_noised: Cell::new(false),
+            best_solution: None,
+            exploration_schedule: None,
+            #[cfg(feature = "std")]
+            stats_handle: OnceCell::new(),
+            search_progress: Cell::new(None),
+            simulations: Cell::new(0),
+            nodes: Cell::new(1),
+            max_nodes: None,
+            rng: RefCell::new(Box::new(StdRng::seed_from_u64(0))),
+            random_tiebreak: false,
+            #[cfg(debug_assertions)]
+            reward_sum: Cell::new(None),
+        }
+    }
+
+    /// Set the tree policy. By default, it is UCT tree policy.
+    /// Any `Fn(wi, ni, np) -> f32` closure can be used, where `np` is ni of the parent node; see [`TreePolicy`] for more.
+    /// The policy is set on every node already in the tree, e.g. after deserializing it, and replaces the schedule of
+    /// [`SearchTree::with_exploration_schedule`] if one was set.
+    pub fn with_tree_policy(mut self, tree_policy: impl TreePolicy + 'static) -> Self {
+        self.set_tree_policy(tree_policy);
+        self
+    }
+
+    /// Change the tree policy of a tree in use, e.g. to play more greedily in the endgame after a [`SearchTree::renew`].
+    /// Like [`SearchTree::with_tree_policy`], the policy replaces the old one on every node already in the tree, so the
+    /// children expanded before the change select with the new policy too, and it is inherited by the nodes expanded later.
+    pub fn set_tree_policy(&mut self, tree_policy: impl TreePolicy + 'static) {
+        self.exploration_schedule = None;
+        let tree_policy: Rc<dyn TreePolicy> = Rc::new(tree_policy);
+        let mut visited = HashSet::new();
+        let mut nodes = vec![self.root_node.clone()];
+        while let Some(node) = nodes.pop() {
+            let mut node = node.borrow_mut();
+            node.tree_policy = tree_policy.clone();
+            if visited.insert(Rc::as_ptr(&node.child_nodes)) {
+                nodes.extend(node.child_nodes.borrow().iter().cloned());
+            }
+        }
+    }
+
+    /// Use the UCT tree policy with an exploration constant that changes during each search, given by
+    /// `schedule(iteration, total)` before every simulation, where `iteration` is the number of simulations the search
+    /// has run so far and `total` the number it runs, e.g. `|iteration, total| 2. * (1. - iteration as f32 / total as f32)`
+    /// to explore early and exploit late. A search bounded by a number of simulations counts them, while a single
+    /// [`SearchTree::step`] is a search of one simulation. It replaces the tree policy like [`SearchTree::with_tree_policy`],
+    /// which replaces the schedule in turn.
+    pub fn with_exploration_schedule(self, schedule: impl Fn(u32, u32) -> f32 + 'static) -> Self {
+        let c = Rc::new(Cell::new(Float::sqrt(2.)));
+        let mut search_tree = self.with_tree_policy(ScheduledUct(c.clone()));
+        search_tree.exploration_schedule = Some((Rc::new(schedule), c));
+        search_tree
+    }
+
+    /// Set the rollout policy used to evaluate newly reached leaves, replacing the evaluator if one was set.
+    /// By default, there is no rollout and the tree is grown until a terminal state is reached in every simulation.
+    pub fn with_rollout_policy(mut self, rollout_policy: impl RolloutPolicy<G> + 'static) -> Self {
+        self.leaf_evaluation = LeafEvaluation::Rollout(Rc::new(rollout_policy));
+        self
+    }
+
+    /// Set the evaluator used to evaluate newly reached leaves instead of rollouts, replacing the rollout policy if one was set.
+    /// Terminal states are still rewarded by their [`GameState::end_status`].
+    pub fn with_evaluator(mut self, evaluator: impl Evaluator<G> + 'static) -> Self {
+        self.leaf_evaluation = LeafEvaluation::Evaluate(Rc::new(evaluator));
+        self
+    }
+
+    /// Cut rollouts off after `max_depth` actions, counting chance outcomes, and reward a rollout that is cut off before
+    /// the game ends by `estimate`'s evaluation of the state it reached, e.g. a heuristic of the material on a board.
+    /// It bounds the cost of every rollout in long games, while rollouts that end in time are rewarded by their outcome.
+    /// The limit applies to the rollout policy through [`RolloutPolicy::rollout_with_cutoff`], which [`RandomRollout`]
+    /// and [`InPlaceRollout`] implement, and to the random rollouts of [`SearchTree::with_max_nodes`].
+    pub fn with_rollout_depth_limit(
+        mut self,
+        max_depth: u32,
+        estimate: impl Evaluator<G> + 'static,
+    ) -> Self {
+        self.rollout_cutoff = Some((max_depth, Rc::new(estimate)));
+        self
+    }
+
+    /// End every rollout that takes `max_steps` actions, counting chance outcomes, with `outcome`, e.g. a draw.
+    /// It is a safety bound for games that can return to earlier positions, like repetitions in chess, or state machines
+    /// that may never reach an end: a random rollout in them could go on without end and hang the whole search.
+    /// Unlike [`SearchTree::with_rollout_depth_limit`], which it can be combined with, the limit is meant to be high
+    /// enough that normal games never reach it. It applies to the rollouts the depth limit applies to.
+    pub fn with_max_rollout_steps(mut self, max_steps: u32, outcome: G::EndStatus) -> Self {
+        self.max_rollout_steps = Some((max_steps, outcome));
+        self
+    }
+
+    /// Back up the heuristic evaluations of `evaluator` by minimax alongside the mean rewards, for implicit minimax
+    /// tree policies like [`implicit_minimax`]. The first time a node is reached at the end of a simulation, it takes
+    /// its end status, or the evaluation of its state by `evaluator`, as its minimax outcome; every other node on the
+    /// path at a decision state takes the minimax outcome of its child best for the player to move, so it gets the
+    /// evaluation at the end of the minimax line through its visited subtree. Any player's reward for the outcome
+    /// is its value for that player, so the backup also suits games that aren't zero-sum. The leaves are still
+    /// evaluated by the rollout policy or the evaluator for the mean rewards, which the final selection is based on.
+    pub fn with_implicit_minimax(mut self, evaluator: impl Evaluator<G> + 'static) -> Self {
+        self.implicit_minimax = Some(Rc::new(evaluator));
+        self
+    }
+
+    /// Set the callback giving the prior probability of each possible action at a state, e.g. the policy head of a neural network.
+    /// The priors are assigned to the children when a node is expanded and can be used by tree policies like [`puct`].
+    /// The priors are normalized over the possible actions, so a policy over every action of the game can be given as is:
+    /// actions that aren't possible are dropped, and missing or negative priors count as zero. Without the callback, or
+    /// if no possible action gets a positive prior, every child gets a uniform prior.
+    pub fn with_priors(mut self, priors: impl Fn(&G) -> Vec<(G::Action, f32)> + 'static) -> Self {
+        self.priors = Some(Rc::new(priors));
+        self
+    }
+
+    /// Mix Dirichlet noise into the priors of the root's children, as AlphaZero does in self-play to explore the actions
+    /// the priors underrate: at the start of the first search from each root, the prior `P` of every child becomes
+    /// `(1 - epsilon) * P + epsilon * noise`, with the noise drawn from `Dir(alpha)` with the RNG of [`SearchTree::with_rng`].
+    /// AlphaZero used an `epsilon` of 0.25 and an `alpha` of about 10 divided by the typical number of actions.
+    ///
+    /// Only the root is noised, and only its decisions, not its chance outcomes. The priors are only read by tree policies
+    /// like [`puct`], so the noise has no effect with the others. The children of the root revealed later, by progressive
+    /// widening or ISMCTS, are not noised.
+    pub fn with_root_dirichlet_noise(mut self, alpha: f32, epsilon: f32) -> Self {
+        self.root_noise = Some((alpha, epsilon));
+        self
+    }
+
+    /// Enable information set MCTS (ISMCTS) for imperfect-information games.
+    /// At the start of each simulation, the root state is replaced by a concrete state sampled by [`GameState::determinize`],
+    /// and the simulation only considers the actions available in that sampled world. Children are matched by action,
+    /// so the statistics are shared across all the sampled worlds, and the tree policy is given how many times a child was
+    /// available in place of the times its parent was selected.
+    ///
+    /// Each node keeps the state of the world it was first created in. Hidden information revealed during the game should be
+    /// modelled as chance outcomes, so that [`SearchTree::renew`] follows the actual observation. With
+    /// [`SearchTree::with_transposition_table`], the nodes reached by different sequences of actions share their statistics
+    /// too, if their states have the same key.
+    pub fn with_ismcts(mut self, ismcts: bool) -> Self {
+        self.ismcts = ismcts;
+        self
+    }
+
+    /// Enable the MCTS-Solver, which proves the outcome of nodes under optimal play from the terminal states up.
+    /// A node is proven once one of its children is a proven win of its player to move, or once all its children are proven,
+    /// and is then treated like a terminal state with that outcome; selection and the final choice of action always take
+    /// a proven win and avoid proven losses. The solver assumes that rewards are between 0 and 1, with 1 for a win and 0 for
+    /// a loss, like the tictactoe example's. Leaves evaluated by rollouts or an evaluator are not proven, chance nodes are
+    /// never proven, and the solver is not used with ISMCTS.
+    pub fn with_solver(mut self, solver: bool) -> Self {
+        self.solver = solver;
+        self
+    }
+
+    /// Enable progressive widening for games with many actions: a node only has `ceil(k * ni^alpha)` children, revealed
+    /// one by one as it is visited, instead of a child for every possible action. The actions are revealed in the order of
+    /// their priors given by [`SearchTree::with_priors`], highest first, and otherwise by the scores of
+    /// [`GameState::heuristic_actions`] or in the order of [`GameState::order_actions`], so any of them can be used
+    /// to put the promising actions first.
+    /// Typical values are `k` between 1 and 3 and `alpha` between 0.25 and 0.5.
+    ///
+    /// Chance outcomes are always expanded at once, and the children of ISMCTS nodes are not widened progressively.
+    /// With the transposition table, the nodes sharing a position's children only reveal them as the first one is visited.
+    pub fn with_progressive_widening(mut self, k: f32, alpha: f32) -> Self {
+        self.widening = Some((k, alpha));
+        self
+    }
+
+    /// Give the unvisited children of a node a first play urgency (FPU) of `value`, instead of selecting every child once
+    /// before the tree policy is applied: once a child of the node has been visited, the unvisited ones are scored by the
+    /// tree policy as if they had been visited once with a reward of `value`, so they compete with the visited ones and the
+    /// search can go deeper into a promising child before trying all of its siblings. The lower `value` is, the longer
+    /// the search sticks with the visited children; with [`puct`], the unvisited children are still tried by their priors.
+    pub fn with_fpu(mut self, value: Float) -> Self {
+        self.fpu = Some(Fpu::Value(value));
+        self
+    }
+
+    /// Give the unvisited children of a node a first play urgency like [`SearchTree::with_fpu`], which is the mean value
+    /// of its visited children reduced by `reduction` times the square root of their total prior,
+    /// as done by Leela Zero: the more of the prior mass is explored, the more the search sticks with the visited children.
+    pub fn with_fpu_reduction(mut self, reduction: Float) -> Self {
+        self.fpu = Some(Fpu::Reduction(reduction));
+        self
+    }
+
+    /// Force playouts into the children of the root, as done by KataGo for self-play with [`puct`]: every child with a
+    /// nonzero prior is selected whenever it has fewer than `k * sqrt(prior * N)` visits, where `N` is the number of visits
+    /// of the root, so that the noise of [`SearchTree::with_root_dirichlet_noise`] gets explored. The forced visits are then
+    /// pruned from [`SearchTree::root_policy`], so that the policy target isn't polluted by them, see KataGo's paper for details.
+    /// The best action is chosen from the visits as they are. A typical `k` is 2.
+    pub fn with_forced_playouts(mut self, k: f32) -> Self {
+        self.forced_playouts = Some(k);
+        self
+    }
+
+    /// Discount the rewards by `gamma` per level, for planning problems where earlier payoffs are worth more: the outcome
+    /// reached `d` levels below a node, at a terminal state or at the leaf evaluated by the rollout policy or the evaluator,
+    /// adds `gamma^d` times its reward to the node. The steps of a rollout are not discounted, since they are not counted.
+    /// By default, `gamma` is 1 and the rewards are not discounted.
+    pub fn with_discount(mut self, gamma: f32) -> Self {
+        self.discount = gamma;
+        self
+    }
+
+    /// Limit the number of nodes in the tree. Once it is reached, no node is expanded any more and the simulations keep
+    /// refining the statistics of the existing nodes; a leaf that can't be expanded is evaluated by the rollout policy or the
+    /// evaluator, or by a [`RandomRollout`] if neither is set. The expansion reaching the limit adds all the children of its
+    /// node, so the tree may exceed the limit by up to the number of possible actions.
+    pub fn with_max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Track the best solution found by the search, see [`SearchTree::best_solution`]: every simulation that ends the game
+    /// records its decisions, and the one whose outcome rewards the player to move at the root most is kept. It is off
+    /// by default, since recording the decisions has a cost; it is meant for single-player optimization, where the best
+    /// solution ever found counts rather than the move played.
+    pub fn with_best_solution(mut self, track: bool) -> Self {
+        self.best_solution = track.then(RefCell::default);
+        self
+    }
+
+    /// Set how the action to play is chosen among the children of the root after a search, see [`FinalSelection`].
+    /// By default, it is the most visited child.
+    pub fn with_final_selection(mut self, final_selection: FinalSelection) -> Self {
+        self.final_selection = final_selection;
+        self
+    }
+
+    /// Set the random number generator used by the search, e.g. by [`RandomRollout`].
+    /// By default, a [`StdRng`] with a fixed seed is used, so that searches with the same game,
+    /// configuration and number of iterations always choose the same action. To replay a game exactly with another seed,
+    /// pass `rand::rngs::StdRng::seed_from_u64(seed)` through the re-exported [`rand`]: the search uses no other source
+    /// of randomness, nor the clock or threads outside of `search_for` and the `parallel` feature.
+    pub fn with_rng(self, rng: impl RngCore + 'static) -> Self {
+        *self.rng.borrow_mut() = Box::new(rng);
+        self
+    }
+
+    /// Set how ties between equally good children are broken, both during selection and when choosing the final action.
+    /// By default, the child that comes first in the order of [`GameState::order_actions`] is chosen;
+    /// with `true`, one of the tied children is chosen uniformly at random using the configured RNG.
+    pub fn with_random_tiebreak(mut self, random_tiebreak: bool) -> Self {
+        self.random_tiebreak = random_tiebreak;
+        self
+    }
+
+    /// Mix the noise of [`SearchTree::with_root_dirichlet_noise`] into the priors of the root's children, once per root.
+    fn add_root_noise(&self) {
+        let Some((alpha, epsilon)) = self.root_noise else {
+            return;
+        };
+        if self.root_noised.replace(true) {
+            return;
+        }
+        let root_node = self.root_node.borrow();
+        if root_node.end_status().is_some()
+            || root_node.state.transition_kind() == TransitionKind::Chance
+        {
+            return;
+        }
+        if !self.ismcts {
+            let children = root_node.child_nodes.borrow().len();
+            root_node.expand(
+                self.priors.as_deref(),
+                self.transpositions.as_deref(),
+                self.widening,
+            );
+            let added = root_node.child_nodes.borrow().len() - children;
+            self.nodes.set(self.nodes.get() + added);
+        }
+        let child_nodes = root_node.child_nodes.borrow();
+        let noise = sample_dirichlet(alpha, child_nodes.len(), &mut **self.rng.borrow_mut());
+        for (node, noise) in child_nodes.iter().zip(noise) {
+            let node = node.borrow();
+            node.prior
+                .set((1. - epsilon) * node.prior.get() + epsilon * noise);
+        }
+    }
+
+    fn simulate_n(&self, n: u32) {
+        self.simulate_n_through(n, None);
+    }
+
+    /// Run `f` as one search of `total` simulations, for the schedule of [`SearchTree::with_exploration_schedule`],
+    /// when it runs them in several parts.
+    fn run_search<T>(&self, total: u32, f: impl FnOnce() -> T) -> T {
+        #[cfg(feature = "tracing")]
+        let _span = self.search_span(total);
+        self.search_progress
+            .set(Some((self.simulations.get(), total)));
+        let result = f();
+        self.search_progress.set(None);
+        result
+    }
+
+    /// The span of a search of `total` simulations, or `None` within a search run by [`SearchTree::run_search`],
+    /// which has its own.
+    #[cfg(feature = "tracing")]
+    fn search_span(&self, total: u32) -> Option<tracing::span::EnteredSpan> {
+        match self.search_progress.get() {
+            Some(_) => None,
+            None => Some(tracing::debug_span!("search", simulations = total).entered()),
+        }
+    }
+
+    /// The simulations done when the running search began and the simulations it runs in total,
+    /// which is made of the next `n` simulations unless [`SearchTree::run_search`] is running.
+    fn search_progress(&self, n: u32) -> (u32, u32) {
+        self.search_progress
+            .get()
+            .unwrap_or((self.simulations.get(), n))
+    }
+
+    /// Set the exploration constant of [`SearchTree::with_exploration_schedule`] for the next simulation of the search
+    /// given by `progress`, see [`SearchTree::search_progress`].
+    fn schedule_exploration(&self, (start, total): (u32, u32)) {
+        if let Some((schedule, c)) = &self.exploration_schedule {
+            let iteration = self.simulations.get().saturating_sub(start);
+            c.set(Float::from(schedule(iteration, total)));
+        }
+    }
+
+    /// Run `n` simulations, all descending into `root_child` from the root if it is given.
+    fn simulate_n_through(&self, n: u32, root_child: Option<&RcNode<G>>) {
+        #[cfg(feature = "tracing")]
+        let _span = self.search_span(n);
+        self.add_root_noise();
+        let root_node = self.root_node.borrow();
+        let mut rng = self.rng.borrow_mut();
+        let mut ctx = self.context(root_child, &mut **rng);
+        let progress = self.search_progress(n);
+        for _ in 0..n {
+            self.schedule_exploration(progress);
+            let determinized = match ctx.ismcts {
+                true => root_node.state.determinize(ctx.rng).map(Rc::new),
+                false => None,
+            };
+            let state = determinized.unwrap_or_else(|| root_node.state.clone());
+            Node::simulate(&self.root_node, state, &mut ctx);
+            self.simulations
+                .set(self.simulations.get().saturating_add(1));
+        }
+        self.publish_stats();
+    }
+
+    /// Replace the statistics of the handle of [`SearchTree::stats_handle`] by the current ones, if it has been created.
+    fn publish_stats(&self) {
+        #[cfg(feature = "std")]
+        if let Some(stats_handle) = self.stats_handle.get() {
+            stats_handle.publish(self.root_stats());
+        }
+    }
+
+    /// The statistics of the root for [`SearchTree::stats_handle`]. The best action is chosen like
+    /// [`SearchTree::best_action`], except that ties keep the order of the children even with
+    /// [`SearchTree::with_random_tiebreak`], so that publishing draws nothing from the RNG of the search.
+    #[cfg(feature = "std")]
+    fn root_stats(&self) -> RootStats<G::Action> {
+        let root_node = self.root_node.borrow();
+        let best_node =
+            root_node.select_final(self.final_selection, self.solver && !self.ismcts, None);
+        RootStats {
+            best_action: best_node.and_then(|node| node.borrow().last_action.clone()),
+            wi: root_node.wi(),
+            ni: root_node.ni(),
+            simulations: self.simulations.get(),
+            nodes: self.nodes.get(),
+            children: self.root_action_values(),
+        }
+    }
+
+    fn context<'a>(
+        &'a self,
+        root_child: Option<&'a RcNode<G>>,
+        rng: &'a mut dyn RngCore,
+    ) -> SearchContext<'a, G> {
+        SearchContext {
+            leaf_evaluation: &self.leaf_evaluation,
+            rollout_cutoff: self
+                .rollout_cutoff
+                .as_ref()
+                .map(|(max_depth, estimate)| (*max_depth, &**estimate)),
+            max_rollout_steps: self
+                .max_rollout_steps
+                .as_ref()
+                .map(|(max_steps, outcome)| (*max_steps, outcome)),
+            implicit_minimax: self.implicit_minimax.as_deref(),
+            priors: self.priors.as_deref(),
+            transpositions: self.transpositions.as_deref(),
+            ismcts: self.ismcts,
+            solver: self.solver && !self.ismcts && self.simultaneous.is_none(),
+            widening: self.widening,
+            fpu: self.fpu,
+            forced_playouts: self.forced_playouts,
+            amaf: self.amaf,
+            discount: self.discount,
+            backup: self.backup,
+            step_rewards: self.step_rewards,
+            simultaneous: self.simultaneous,
+            random_tiebreak: self.random_tiebreak,
+            nodes: &self.nodes,
+            max_nodes: self.max_nodes,
+            root_child,
+            defer_leaves: false,
+            best_solution: self.best_solution.as_ref(),
+            #[cfg(debug_assertions)]
+            reward_sum: &self.reward_sum,
+            rng,
+        }
+    }
+
+    /// Run `n` simulations whose new leaves are evaluated by `evaluate_batch`, `batch_size` of them at a time.
+    /// A batch is evaluated early if a descent reaches a leaf of the batch again.
+    fn simulate_n_batched(
+        &self,
+        n: u32,
+        batch_size: usize,
+        evaluate_batch: &mut BatchEvaluator<G, G::EndStatus, G::Action>,
+    ) {
+        #[cfg(feature = "tracing")]
+        let _span = self.search_span(n);
+        let mut rng = self.rng.borrow_mut();
+        let mut ctx = self.context(None, &mut **rng);
+        ctx.defer_leaves = true;
+        let progress = self.search_progress(n);
+        let mut batch = vec![];
+        let mut done = 0;
+        while done + batch.len() < n as usize {
+            self.schedule_exploration(progress);
+            let state = self.root_node.borrow().state.clone();
+            let (descent, outcome) = Node::descend(&self.root_node, state, &mut ctx);
+            if let Some(outcome) = outcome {
+                descent.backpropagate(&outcome, &ctx);
+                done += 1;
+                self.simulations
+                    .set(self.simulations.get().saturating_add(1));
+                continue;
+            }
+            if batch
+                .iter()
+                .any(|pending: &Descent<G>| Rc::ptr_eq(pending.leaf(), descent.leaf()))
+            {
+                done += self.evaluate_batch(&mut batch, evaluate_batch, &ctx);
+                continue;
+            }
+            descent.add_virtual_loss();
+            batch.push(descent);
+            if batch.len() >= batch_size {
+                done += self.evaluate_batch(&mut batch, evaluate_batch, &ctx);
+            }
+        }
+        self.evaluate_batch(&mut batch, evaluate_batch, &ctx);
+        self.publish_stats();
+    }
+
+    /// Evaluate the leaves of `batch`, expand them with the priors given, and backpropagate their outcomes.
+    /// Return the number of simulations completed.
+    fn evaluate_batch(
+        &self,
+        batch: &mut Vec<Descent<G>>,
+        evaluate_batch: &mut BatchEvaluator<G, G::EndStatus, G::Action>,
+        ctx: &SearchContext<G>,
+    ) -> usize {
+        if batch.is_empty() {
+            return 0;
+        }
+        let states: Vec<&G> = batch.iter().map(|descent| descent.state.as_ref()).collect();
+        let evaluations = evaluate_batch(&states);
+        assert_eq!(
+            evaluations.len(),
+            batch.len(),
+            "the batch evaluator must return one evaluation per state"
+        );
+        let completed = batch.len();
+        for (descent, (outcome, priors)) in batch.drain(..).zip(evaluations) {
+            descent.remove_virtual_loss();
+            if !ctx.at_capacity() {
+                let leaf = descent.leaf().borrow();
+                let children = leaf.child_nodes.borrow().len();
+                let priors = move |_: &G| priors.clone();
+                leaf.expand(Some(&priors), ctx.transpositions, ctx.widening);
+                let added = leaf.child_nodes.borrow().len() - children;
+                ctx.nodes.set(ctx.nodes.get() + added);
+            }
+            descent.backpropagate(&outcome, ctx);
+            self.simulations
+                .set(self.simulations.get().saturating_add(1));
+        }
+        completed
+    }
+
+    fn select_final(&self) -> Option<RcNode<G>> {
+        let mut rng = self.rng.borrow_mut();
+        let rng: Option<&mut dyn RngCore> = match self.random_tiebreak {
+            true => Some(&mut **rng),
+            false => None,
+        };
+        self.root_node
+            .borrow()
+            .select_final(self.final_selection, self.solver && !self.ismcts, rng)
+    }
+
+    /// Search for the best action by running `n` simulations. Repeated searches refine the same tree rather than starting
+    /// over, so two searches of 100 simulations are worth one of 200; see [`SearchTree::simulations_done`] for the total.
+    pub fn search(&self, n: u32) -> Option<G::Action> {
+        self.simulate_n(n);
+        self.best_action()
+    }
+
+    /// Search for the best action like [`SearchTree::search`], with a budget growing with the branching factor of the root:
+    /// `base + per_action * k` simulations for the `k` children of the root, so that a position with many moves to choose
+    /// from is searched longer than a forced one. The root is expanded first to learn `k`, which counts the children added
+    /// so far with progressive widening, and the possible actions of the root's state in ISMCTS. The budget saturates at
+    /// `u32::MAX`.
+    pub fn search_adaptive(&self, base: u32, per_action: u32) -> Option<G::Action> {
+        let root_node = self.root_node.borrow();
+        let k = match root_node.end_status() {
+            Some(_) => 0,
+            None if self.ismcts => actions_of(root_node.state.as_ref()).len(),
+            None => {
+                let children = root_node.child_nodes.borrow().len();
+                root_node.expand(
+                    self.priors.as_deref(),
+                    self.transpositions.as_deref(),
+                    self.widening,
+                );
+                let expanded = root_node.child_nodes.borrow().len();
+                self.nodes.set(self.nodes.get() + expanded - children);
+                expanded
+            }
+        };
+        drop(root_node);
+        let k = u32::try_from(k).unwrap_or(u32::MAX);
+        self.search(base.saturating_add(per_action.saturating_mul(k)))
+    }
+
+    /// Search for the best action like [`SearchTree::search`], but decide to resign instead of taking it if the position
+    /// is hopeless for the player to move at the root: when the solver of [`SearchTree::with_solver`] has proven the root
+    /// lost, or when the mean value of the best action is below `threshold`, e.g. 0.05 for rewards in `[0, 1]`.
+    /// A chance state always takes its sampled outcome. It is none if the root has no possible action.
+    pub fn decide_with_resign(&self, n: u32, threshold: Float) -> Option<Decision<G::Action>> {
+        let action = self.search(n)?;
+        let root_node = self.root_node.borrow();
+        if root_node.state.transition_kind() == TransitionKind::Chance {
+            return Some(Decision::Move(action));
+        }
+        let player = root_node.state.player();
+        let proven_lost = root_node
+            .solved
+            .get()
+            .is_some_and(|outcome| player.reward_when_outcome_is(outcome) <= LOSS_REWARD);
+        let value = root_node
+            .find_child(&action)
+            .map(|node| node.borrow().mean_value());
+        match proven_lost || value.is_some_and(|value| value < threshold) {
+            true => Some(Decision::Resign),
+            false => Some(Decision::Move(action)),
+        }
+    }
+
+    /// Search for the best solution from the root by Nested Monte Carlo Search instead of the tree, for deterministic
+    /// single-player optimization, where it often beats the tree search. A search at level 0 is a playout of the rollout
+    /// policy, or of [`RandomRollout`] if the leaves aren't evaluated by rollouts; a search at level `l` plays the game
+    /// from the root, trying every possible action at each state with a search at level `l - 1` from the state it leads
+    /// to, and plays the next move of the best sequence found so far. The best sequence is the one whose outcome rewards
+    /// the player to move at the root most, and is returned with its end status, or `None` if the root has ended.
+    ///
+    /// The tree and its statistics are left as they are, but the best solution of [`SearchTree::with_best_solution`] is
+    /// updated. A search at level `l` runs about `(b * d)^l` playouts for `b` actions per state and games of `d` moves,
+    /// so levels above 3 are rarely affordable. Like [`SearchTree::best_solution`], the sequence leaves out the outcomes
+    /// of chance states, which are sampled on the way.
+    pub fn search_nmcs(&self, level: u32) -> Option<(Vec<G::Action>, G::EndStatus)> {
+        let state = self.root_node.borrow().state.clone();
+        if state.is_terminal() {
+            return None;
+        }
+        let rollout_policy: &dyn RolloutPolicy<G> = match &self.leaf_evaluation {
+            LeafEvaluation::Rollout(rollout_policy) => &**rollout_policy,
+            _ => &RandomRollout,
+        };
+        let mut rng = self.rng.borrow_mut();
+        let (moves, outcome, reward) = nested_search(
+            state.as_ref(),
+            level,
+            &state.player(),
+            rollout_policy,
+            &mut **rng,
+        );
+        if let Some(best_solution) = &self.best_solution {
+            let mut best_solution = best_solution.borrow_mut();
+            if best_solution
+                .as_ref()
+                .is_none_or(|(_, _, best)| reward > *best)
+            {
+                *best_solution = Some((moves.clone(), outcome.clone(), reward));
+            }
+        }
+        Some((moves, outcome))
+    }
+
+    /// Search for the best action like [`SearchTree::search`] with at most `max_n` simulations, but stop early once the
+    /// best action is settled: after at least `min_n` simulations, as soon as the most visited child of the root leads
+    /// the second most visited one by more than `margin` visits, and by more than the simulations left, so that no other
+    /// child could overtake it within the budget. Return the best action with the number of simulations run.
+    pub fn search_until_converged(
+        &self,
+        max_n: u32,
+        min_n: u32,
+        margin: Float,
+    ) -> (Option<G::Action>, u32) {
+        let mut iterations = 0;
+        self.run_search(max_n, || {
+            while iterations < max_n {
+                self.simulate_n(1);
+                iterations += 1;
+                if iterations < min_n {
+                    continue;
+                }
+                let top = self.top_k_actions(2);
+                let best = top.first().map_or(0., |(_, _, ni)| *ni);
+                let second = top.get(1).map_or(0., |(_, _, ni)| *ni);
+                let lead = best - second;
+                if lead > margin && lead > (max_n - iterations) as Float {
+                    break;
+                }
+            }
+        });
+        (self.best_action(), iterations)
+    }
+
+    /// Search for the best action like [`SearchTree::search`], but stop early as soon as `cancel` is set, e.g. from another
+    /// thread when the user interrupts the move. The flag is checked before every simulation, and the best action according to
+    /// the simulations run so far is returned; the tree stays valid for further searches and [`SearchTree::renew`].
+    pub fn search_cancellable(&self, n: u32, cancel: &AtomicBool) -> Option<G::Action> {
+        self.run_search(n, || {
+            for _ in 0..n {
+                if cancel.load(atomic::Ordering::Relaxed) {
+                    break;
+                }
+                self.simulate_n(1);
+            }
+        });
+        self.best_action()
+    }
+
+    /// Search for the best action like [`SearchTree::search`], but evaluate the new leaves in batches, e.g. with one call
+    /// to a neural network per batch. Every simulation descends the tree until it reaches a leaf that has not been expanded,
+    /// which is queued with a virtual loss, an extra visit without reward at every node of its path, so that the next
+    /// simulations spread over other leaves. Once `batch_size` leaves are queued, `evaluate_batch` is called with their
+    /// states and returns an outcome and the priors of the possible actions for each of them, in the same order, as
+    /// [`Evaluator::evaluate`] and [`SearchTree::with_priors`] would. The leaves are then expanded with these priors
+    /// and the outcomes backpropagated, replacing the virtual losses.
+    ///
+    /// A batch is evaluated before it is full when a simulation reaches one of its leaves again, and a `batch_size` of 0
+    /// is treated as 1. The leaf evaluation and the priors of the tree are not used, except at the root noise, which is
+    /// added once the root has been evaluated. An ISMCTS tree can't defer its leaves and is searched by [`SearchTree::search`].
+    pub fn search_batched(
+        &self,
+        n: u32,
+        batch_size: usize,
+        mut evaluate_batch: impl FnMut(&[&G]) -> Vec<(G::EndStatus, Vec<(G::Action, f32)>)>,
+    ) -> Option<G::Action> {
+        if self.ismcts {
+            return self.search(n);
+        }
+        self.run_search(n, || {
+            let mut done = 0;
+            if !self.root_node.borrow().is_expanded() && n > 0 {
+                self.simulate_n_batched(1, 1, &mut evaluate_batch);
+                done = 1;
+            }
+            self.add_root_noise();
+            self.simulate_n_batched(n - done, batch_size.max(1), &mut evaluate_batch);
+        });
+        self.best_action()
+    }
+
+    /// Search for the best action like [`SearchTree::search`], calling `f` with the progress of the search after every
+    /// `every` simulations, and after the last one if `n` is not a multiple of `every`,
+    /// e.g. to print the "info" lines of a game engine while it is thinking.
+    /// No node is borrowed while `f` runs, so it may inspect the tree, e.g. with [`SearchTree::principal_variation`].
+    /// An `every` of 0 is treated as 1.
+    pub fn search_with_callback(
+        &self,
+        n: u32,
+        every: u32,
+        mut f: impl FnMut(&SearchProgress<G::Action>),
+    ) -> Option<G::Action> {
+        let every = every.max(1);
+        let mut iterations = 0;
+        self.run_search(n, || {
+            while iterations < n {
+                let chunk = every.min(n - iterations);
+                self.simulate_n(chunk);
+                iterations += chunk;
+                let best = self.select_final();
+                f(&SearchProgress {
+                    iterations,
+                    best_action: best
+                        .as_ref()
+                        .and_then(|node| node.borrow().last_action.clone()),
+                    mean_value: best.map_or(0., |node| node.borrow().mean_value()),
+                    depth: self.principal_nodes().len() - 1,
+                    nodes: self.node_count(),
+                });
+            }
+        });
+        self.best_action()
+    }
+
+    /// Search for the best action with Sequential Halving at the root, which spends a fixed budget of `n` simulations
+    /// on identifying the best action more efficiently than UCT does, when the tree is not reused for later moves.
+    /// The candidates start as all children of the root; each round gives every candidate an equal share of the budget
+    /// left for the round, descending below it by the tree policy as usual, and keeps the better half of the candidates
+    /// by mean value, until `ceil(log2(k))` rounds leave one of the `k` children; ties keep the order of
+    /// [`GameState::order_actions`]. A candidate gets at least one simulation per round while the budget lasts, and the
+    /// rounds stop early once it runs out; the simulations left after the last round, e.g. all of them if the root has a
+    /// single child, go to the last candidate, so exactly `n` simulations are run.
+    ///
+    /// The root of an ISMCTS tree, or a chance state at the root, is searched by [`SearchTree::search`] instead.
+    pub fn search_sequential_halving(&self, n: u32) -> Option<G::Action> {
+        let root_node = self.root_node.borrow();
+        if self.ismcts
+            || root_node.end_status().is_some()
+            || root_node.state.transition_kind() == TransitionKind::Chance
+        {
+            drop(root_node);
+            return self.search(n);
+        }
+        let children = root_node.child_nodes.borrow().len();
+        root_node.expand(
+            self.priors.as_deref(),
+            self.transpositions.as_deref(),
+            self.widening,
+        );
+        self.nodes
+            .set(self.nodes.get() + root_node.child_nodes.borrow().len() - children);
+        let mut candidates = root_node.child_nodes();
+        drop(root_node);
+        if candidates.is_empty() {
+            // Like the search, which can't descend from a state with no action
+            return self.search(n);
+        }
+
+        let mut rounds = candidates.len().next_power_of_two().trailing_zeros();
+        let mut budget = n;
+        self.run_search(n, || {
+            while candidates.len() > 1 && budget > 0 {
+                let share = (budget / rounds.max(1) / candidates.len() as u32).max(1);
+                for candidate in &candidates {
+                    let share = share.min(budget);
+                    self.simulate_n_through(share, Some(candidate));
+                    budget -= share;
+                }
+                rounds = rounds.saturating_sub(1);
+                // Stable, so that candidates with equal values keep the order of `possible_actions`
+                candidates.sort_by(|a, b| {
+                    let (a, b) = (a.borrow().mean_value(), b.borrow().mean_value());
+                    b.partial_cmp(&a).unwrap_or(Ordering::Equal)
+                });
+                candidates.truncate(candidates.len().div_ceil(2));
+            }
+            self.simulate_n_through(budget, Some(&candidates[0]));
+        });
+        let best = candidates.first()?.borrow().last_action.clone();
+        best
+    }
+
+    /// Run exactly one simulation, so that the search can be interleaved with other work, e.g. in an event loop.
+    /// Read the result with [`SearchTree::best_action`] when it's time to move.
+    pub fn step(&self) {
+        self.simulate_n(1);
+    }
+
+    /// The decisions and the end status of the simulation with the largest reward for the player to move at the root
+    /// among those that reached the end of the game since the root was set, if [`SearchTree::with_best_solution`]
+    /// tracks it; ties keep the first one found. The decisions are those of every player from the root, with the
+    /// outcomes of chance states left out. Simulations whose leaf is evaluated without playing to the end, by an
+    /// evaluator, a cut-off or limited rollout, the solver or past the limit of [`SearchTree::with_max_nodes`], are not counted.
+    pub fn best_solution(&self) -> Option<(Vec<G::Action>, G::EndStatus)> {
+        let best_solution = self.best_solution.as_ref()?.borrow();
+        best_solution
+            .as_ref()
+            .map(|(moves, outcome, _)| (moves.clone(), outcome.clone()))
+    }
+
+    /// The action of the root chosen by [`SearchTree::with_final_selection`], by default the most visited one,
+    /// from the statistics of the simulations run so far. It runs no simulation and leaves the tree as it is,
+    /// so it may be called at any time, e.g. to refresh the move shown to the user between steps of the search.
+    pub fn best_action(&self) -> Option<G::Action> {
+        let selected_node = self.select_final();
+        selected_node.and_then(|v| v.borrow().last_action.clone())
+    }
+
+    /// Search for the best action like [`SearchTree::search`], and return it together with the statistics of the root's children.
+    pub fn search_stats(&self, n: u32) -> Option<SearchResult<G::Action>> {
+        self.simulate_n(n);
+        let selected_node = self.select_final()?;
+        let selected_node = selected_node.borrow();
+        let children = self
+            .root_node
+            .borrow()
+            .child_nodes
+            .borrow()
+            .iter()
+            .filter_map(|node| {
+                let node = node.borrow();
+                Some((node.last_action.clone()?, node.ni(), node.mean_value()))
+            })
+            .collect();
+        Some(SearchResult {
+            action: selected_node.last_action.clone()?,
+            visits: selected_node.ni(),
+            mean_value: selected_node.mean_value(),
+            simulations_run: n,
+            children,
+        })
+    }
+
+    /// The action, mean value and times of selection of each child of the root, in the order of [`GameState::order_actions`],
+    /// e.g. to display the assessment of every candidate move. The mean value is the reward of the player to move at the root,
+    /// or 0 if the child has not been visited. It is empty if the root has not been expanded.
+    pub fn root_action_values(&self) -> Vec<(G::Action, Float, Float)> {
+        self.root_node
+            .borrow()
+            .child_nodes
+            .borrow()
+            .iter()
+            .filter_map(|node| {
+                let node = node.borrow();
+                Some((node.last_action.clone()?, node.mean_value(), node.ni()))
+            })
+            .collect()
+    }
+
+    /// The `k` most visited actions of the root with their mean value and times of selection like [`SearchTree::root_action_values`],
+    /// the most visited first, e.g. for the multiple principal variations of a game engine. Children visited equally often
+    /// keep the order of [`GameState::order_actions`], and fewer than `k` are returned if the root has fewer children.
+    pub fn top_k_actions(&self, k: usize) -> Vec<(G::Action, Float, Float)> {
+        let mut action_values = self.root_action_values();
+        action_values.sort_by(|(_, _, a), (_, _, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+        action_values.truncate(k);
+        action_values
+    }
+
+    /// How sure the search is of its most visited action: the ratio of the visits of the most visited child of the root
+    /// to those of the second most visited one, e.g. to stop searching early once it exceeds a threshold. It is infinite
+    /// if no other child has been visited, and none if the root has no visited child. It runs no simulation.
+    pub fn best_action_confidence(&self) -> Option<Float> {
+        let top = self.top_k_actions(2);
+        let best = top.first().map(|(_, _, ni)| *ni).filter(|&ni| ni > 0.)?;
+        let second = top.get(1).map_or(0., |(_, _, ni)| *ni);
+        Some(best / second)
+    }
+
+    /// The Shannon entropy, in nats, of the visits of the root's children normalized into a distribution: 0 when one action
+    /// has all the visits, and `ln k` when the `k` children are visited equally often. A low entropy means that the search
+    /// has settled on few actions, e.g. to keep searching until it drops below a threshold. It is 0 if no child has been
+    /// visited, and it runs no simulation.
+    pub fn root_visit_entropy(&self) -> Float {
+        let root_node = self.root_node.borrow();
+        let visits: Vec<Float> = root_node
+            .child_nodes
+            .borrow()
+            .iter()
+            .map(|node| node.borrow().ni.get())
+            .collect();
+        let total: Float = visits.iter().sum();
+        if total <= 0. {
+            return 0.;
+        }
+        visits
+            .iter()
+            .filter(|&&ni| ni > 0.)
+            .map(|&ni| {
+                let p = ni / total;
+                -p * p.ln()
+            })
+            .sum()
+    }
+
+    /// The visit-count distribution over the actions of the root, i.e. the share of each child's `ni` among its siblings.
+    /// This is the usual policy target for self-play training. It is empty if the root has not been expanded.
+    /// With [`SearchTree::with_forced_playouts`], the forced visits are pruned from the counts first.
+    pub fn root_policy(&self) -> Vec<(G::Action, f32)> {
+        self.root_policy_with_temperature(1.)
+    }
+
+    /// Like [`SearchTree::root_policy`], but with each child's `ni` raised to `1 / temperature` before normalizing.
+    /// A temperature above 1 flattens the distribution, and one approaching 0 concentrates it on the most visited children.
+    /// If no child has been visited yet, the distribution is uniform.
+    pub fn root_policy_with_temperature(&self, temperature: f32) -> Vec<(G::Action, f32)> {
+        let root_node = self.root_node.borrow();
+        let visits = match self.forced_playouts {
+            Some(k) => root_node.pruned_visits(k),
+            None => root_node
+                .child_nodes
+                .borrow()
+                .iter()
+                .map(|node| node.borrow().ni())
+                .collect(),
+        };
+        let max_visits = visits.iter().copied().fold(0., Float::max);
+        // Normalizing by the maximum keeps `ni^(1/temperature)` from overflowing at low temperatures
+        let weights: Vec<_> = root_node
+            .child_nodes
+            .borrow()
+            .iter()
+            .zip(visits)
+            .filter_map(|(node, ni)| {
+                let weight = match max_visits > 0. {
+                    true => (ni / max_visits).powf(Float::from(1. / temperature)),
+                    false => 1.,
+                };
+                Some((node.borrow().last_action.clone()?, weight))
+            })
+            .collect();
+        let total: Float = weights.iter().map(|(_, weight)| weight).sum();
+        weights
+            .into_iter()
+            .map(|(action, weight)| (action, (weight / total) as f32))
+            .collect()
+    }
+
+    /// Sample an action of the root with probabilities from [`SearchTree::root_policy_with_temperature`],
+    /// which reuses the statistics of previous searches without running more simulations.
+    /// It approaches picking the most visited action as `temperature` approaches 0, and returns `None` if the root has not been expanded.
+    ///
+    /// The sample is drawn from the RNG of [`SearchTree::with_rng`], the same one used by the search itself,
+    /// so a game generated with the same seed, configuration and calls is reproduced exactly.
+    pub fn sample_action(&self, temperature: f32) -> Option<G::Action> {
+        let policy = self.root_policy_with_temperature(temperature);
+        sample_weighted(policy, &mut **self.rng.borrow_mut())
+    }
+
+    /// The principal variation: the line of actions the search believes in, found by following the most visited child
+    /// from the root until an unexpanded or terminal node. Ties are broken by the order of [`GameState::order_actions`].
+    pub fn principal_variation(&self) -> Vec<G::Action> {
+        self.principal_nodes()
+            .iter()
+            .skip(1)
+            .filter_map(|node| node.borrow().last_action.clone())
+            .collect()
+    }
+
+    /// The nodes of the principal variation, starting with the root.
+    fn principal_nodes(&self) -> Vec<RcNode<G>> {
+        let mut nodes = vec![self.root_node.clone()];
+        let mut visited = vec![];
+        loop {
+            // A transposition can lead back to a position already on the line
+            let node = nodes.last().unwrap().clone();
+            let child_nodes = node.borrow().child_nodes.clone();
+            if visited.iter().any(|seen| Rc::ptr_eq(seen, &child_nodes)) {
+                break;
+            }
+            visited.push(child_nodes);
+            match node
+                .borrow()
+                .select_most_visited(self.solver && !self.ismcts, None)
+            {
+                Some(next) if next.borrow().ni.get() > 0. => nodes.push(next),
+                _ => break,
+            };
+        }
+        nodes
+    }
+
+    /// Drop the subtrees below the nodes with fewer than `min_visits` visits, to reclaim the memory of the branches the
+    /// search has given up on. The nodes themselves are kept as leaves with their statistics, and are expanded again
+    /// if the search comes back to them. The root and the nodes of [`SearchTree::principal_variation`] are never pruned.
+    pub fn prune_below(&mut self, min_visits: Float) {
+        self.prune(|node, _| node.ni.get() >= min_visits);
+    }
+
+    /// Drop the nodes more than `depth` actions below the root, except those of [`SearchTree::principal_variation`],
+    /// like [`SearchTree::prune_below`] does. A subtree shared through the transposition table is pruned at the depth
+    /// it is first reached at.
+    pub fn prune_to_depth(&mut self, depth: usize) {
+        self.prune(|_, node_depth| node_depth < depth);
+    }
+
+    /// Collapse the nodes for which `keep`, given the node and its depth, is false, leaving the principal variation intact.
+    fn prune(&mut self, keep: impl Fn(&Node<G>, usize) -> bool) {
+        let principal_nodes = self.principal_nodes();
+        let mut visited = HashSet::new();
+        let mut nodes = VecDeque::from([(self.root_node.clone(), 0)]);
+        // Breadth first, so that a shared subtree is seen at its shallowest depth first
+        while let Some((node, depth)) = nodes.pop_front() {
+            let principal = principal_nodes.iter().any(|n| Rc::ptr_eq(n, &node));
+            if !principal && !keep(&node.borrow(), depth) {
+                node.borrow_mut().collapse();
+                continue;
+            }
+            let child_nodes = node.borrow().child_nodes.clone();
+            if !visited.insert(Rc::as_ptr(&child_nodes)) {
+                continue;
+            }
+            let child_nodes = child_nodes.borrow();
+            nodes.extend(child_nodes.iter().map(|child| (child.clone(), depth + 1)));
+        }
+        if let Some(transpositions) = &self.transpositions {
+            transpositions.prune();
+        }
+        self.nodes.set(Node::count(&self.root_node));
+    }
+
+    /// Merge the statistics of `other`, a search tree of the same game state, into this tree, e.g. of a search run on
+    /// another machine and sent over by serialization. The nodes are matched by their actions from the root: the rewards,
+    /// visits, availabilities and AMAF statistics of the nodes in both trees are summed, the nodes only in `other` are added
+    /// with their statistics and subtrees, and those only in this tree are kept as they are. [`SearchTree::simulations_done`]
+    /// is summed too, while the configuration of this tree is kept. Children shared through the transposition table are
+    /// merged once, through the first node of their position that is reached.
+    pub fn merge(&mut self, other: &SearchTree<G>) {
+        let mut visited = HashSet::new();
+        let mut nodes = vec![(self.root_node.clone(), other.root_node.clone())];
+        while let Some((node, other_node)) = nodes.pop() {
+            let node = node.borrow();
+            let other_node = other_node.borrow();
+            node.add_statistics(&other_node);
+            if !visited.insert(Rc::as_ptr(&node.child_nodes)) {
+                continue;
+            }
+            // A leaf takes the actions `other` has yet to widen to, so none of them is lost
+            if !node.is_expanded() && node.pending.borrow().is_empty() {
+                *node.pending.borrow_mut() = other_node.pending.borrow().clone();
+            }
+            for other_child in other_node.child_nodes.borrow().iter() {
+                let (action, prior) = {
+                    let other_child = other_child.borrow();
+                    (other_child.last_action.clone(), other_child.prior.get())
+                };
+                let Some(action) = action else {
+                    continue;
+                };
+                let child = node
+                    .find_child(&action)
+                    .or_else(|| node.reveal(&action, self.transpositions.as_deref()))
+                    .unwrap_or_else(|| {
+                        node.add_child(action, prior, self.transpositions.as_deref())
+                    });
+                nodes.push((child, other_child.clone()));
+            }
+        }
+        self.nodes.set(Node::count(&self.root_node));
+        self.simulations.set(
+            self.simulations
+                .get()
+                .saturating_add(other.simulations.get()),
+        );
+    }
+
+    /// Add `visits` visits rewarded `wins` in total to the root's child of `action`, and to the root, as if that many
+    /// simulations had gone through it, expanding the root if needed. It warm-starts the search from precomputed statistics,
+    /// e.g. of an opening book, so that selection favours the book moves from the start: a seeded child counts as visited,
+    /// and its mean value is the seeded `wins / visits` until real simulations add to it.
+    /// The seeded visits count towards [`SearchTree::best_action`] and the other statistics like real ones.
+    pub fn inject_statistics(
+        &self,
+        action: &G::Action,
+        wins: Float,
+        visits: Float,
+    ) -> Result<(), InjectError> {
+        let root_node = self.root_node.borrow();
+        if root_node.end_status().is_some() {
+            return Err(InjectError::GameAlreadyEnded);
+        }
+        let children = root_node.child_nodes.borrow().len();
+        root_node.expand(
+            self.priors.as_deref(),
+            self.transpositions.as_deref(),
+            self.widening,
+        );
+        let node = root_node
+            .find_child(action)
+            .or_else(|| root_node.reveal(action, self.transpositions.as_deref()));
+        let added = root_node.child_nodes.borrow().len() - children;
+        self.nodes.set(self.nodes.get() + added);
+        let node = node.ok_or(InjectError::ActionNotAvailable)?;
+        // The seeded rewards are taken to be all equal to their mean
+        let sum_sq = match visits > 0. {
+            true => wins * wins / visits,
+            false => 0.,
+        };
+        for node in [&node, &self.root_node] {
+            let node = node.borrow();
+            node.wi.set(node.wi.get() + wins);
+            node.ni.set(node.ni.get() + visits);
+            node.sum_sq.set(node.sum_sq.get() + sum_sq);
+        }
+        Ok(())
+    }
+
+    /// Expand every node within `depth` plies of the root with all of its children, whatever its visits, e.g. to build
+    /// the complete tree of a short game or of a tactical position before searching it: with [`SearchTree::with_solver`],
+    /// the search then proves the positions whose outcome is decided within those plies. Each decision or chance event
+    /// is a ply. Terminal nodes are not expanded, the nodes already expanded keep their children and statistics, and
+    /// the actions pending in progressive widening are all revealed. The expansion stops once the tree reaches the limit
+    /// of [`SearchTree::with_max_nodes`]. It does nothing with ISMCTS, whose children depend on the determinizations.
+    pub fn expand_to_depth(&self, depth: usize) {
+        if self.ismcts {
+            return;
+        }
+        self.root_node.borrow().expand_to_depth(
+            depth,
+            self.priors.as_deref(),
+            self.transpositions.as_deref(),
+            &self.nodes,
+            self.max_nodes,
+        );
+    }
+
+    /// Move to the next state and renew the root node with given action.
+    ///
+    /// The child of the action becomes the new root together with its subtree, so the next search starts from the
+    /// statistics of the simulations that already went through it. The whole subtree is kept in memory though, which
+    /// can be most of the tree after a long search; use [`SearchTree::renew_fresh`] to start over instead,
+    /// or [`SearchTree::prune_below`] and [`SearchTree::with_max_nodes`] to keep the reused tree small.
+    pub fn renew(&mut self, action: &G::Action) -> Result<(), RenewError> {
+        let root_node = self.root_node.borrow();
+        if root_node.end_status().is_some() {
+            return Err(RenewError::GameAlreadyEnded);
+        }
+        root_node.expand(
+            self.priors.as_deref(),
+            self.transpositions.as_deref(),
+            self.widening,
+        );
+        drop(root_node);
+
+        let root_node = self.root_node.borrow();
+        let mut new_root_node = root_node
+            .find_child(action)
+            .or_else(|| root_node.reveal(action, self.transpositions.as_deref()));
+        // In ISMCTS, the root only has children for the actions seen in the sampled worlds
+        if new_root_node.is_none() && self.ismcts {
+            let state = &root_node.state;
+            if is_available(state.as_ref(), action) {
+                let node = root_node.derive_child(state, action.clone(), 0.);
+                root_node.push_child(node.clone());
+                new_root_node = Some(node);
+            }
+        }
+
+        drop(root_node);
+
+        if let Some(node) = new_root_node {
+            // The new root has no parent, even while the old root is still referenced from outside
+            *node.borrow().parent.borrow_mut() = Weak::new();
+            self.root_node = node;
+            if let Some(transpositions) = &self.transpositions {
+                transpositions.prune();
+            }
+            self.nodes.set(Node::count(&self.root_node));
+            self.simulations.set(0);
+            self.root_noised.set(false);
+            self.forget_best_solution();
+            return Ok(());
+        }
+        Err(RenewError::ActionNotAvailable)
+    }
+
+    /// Move to the next state like [`SearchTree::renew`], but with a brand-new root node for the state reached by `action`,
+    /// dropping the whole tree. This frees its memory right away, and is a clean slate when the subtree of the action
+    /// is not worth reusing, e.g. when it was barely searched, at the cost of the simulations already spent on it.
+    /// The new root uses the same tree policy and configuration.
+    pub fn renew_fresh(&mut self, action: &G::Action) -> Result<(), RenewError> {
+        let root_node = self.root_node.borrow();
+        if root_node.end_status().is_some() {
+            return Err(RenewError::GameAlreadyEnded);
+        }
+        if !is_available(root_node.state.as_ref(), action) {
+            return Err(RenewError::ActionNotAvailable);
+        }
+        let new_root_node = root_node.derive_child(&root_node.state, action.clone(), 1.);
+        drop(root_node);
+
+        self.replace_root(new_root_node);
+        Ok(())
+    }
+
+    /// Start over from `state`, e.g. for the next game of a self-play loop, with a brand-new root node like
+    /// [`SearchTree::renew_fresh`] makes, dropping the whole tree. The tree policy, the RNG and the rest of the configuration
+    /// are kept, so a tree can be configured once and reused for any number of games.
+    pub fn reset(&mut self, state: Rc<G>) {
+        let root_node = self.root_node.borrow().renewed(state).into_rc();
+        self.replace_root(root_node);
+    }
+
+    /// Make a new node without statistics the root, dropping the old tree.
+    fn replace_root(&mut self, root_node: RcNode<G>) {
+        *root_node.borrow().parent.borrow_mut() = Weak::new();
+        self.root_node = root_node;
+        if let Some(transpositions) = &self.transpositions {
+            transpositions.prune();
+            transpositions.share(&self.root_node);
+        }
+        self.nodes.set(1);
+        self.simulations.set(0);
+        self.root_noised.set(false);
+        self.forget_best_solution();
+    }
+
+    /// Forget the best solution found from the old root, whose decisions don't start from the new one.
+    fn forget_best_solution(&self) {
+        if let Some(best_solution) = &self.best_solution {
+            best_solution.take();
+        }
+    }
+
+    /// Play the game from the current state to its end and return the end status, searching every move of every player
+    /// on this tree and stepping forward with [`SearchTree::renew`], so each search reuses the subtree of the last one.
+    /// The move of a player takes `budget(&player)` simulations, which allows different strengths for engine-vs-engine
+    /// evaluation; a budget of 0 is treated as 1. The outcome of a chance state is sampled by its probabilities instead.
+    /// Players with different budgets don't share statistics though: when the budget changes from the last search,
+    /// the search starts over from a new root, so that a weak player doesn't profit from the simulations of a strong one.
+    /// `on_move` is called with the state and the action taken before every step, e.g. to record self-play data.
+    pub fn play_out(
+        &mut self,
+        mut budget: impl FnMut(&G::Player) -> u32,
+        mut on_move: impl FnMut(&G, &G::Action),
+    ) -> G::EndStatus {
+        let mut last_budget = None;
+        loop {
+            let state = self.get_game_state();
+            if let Some(outcome) = end_status_of(state.as_ref()) {
+                return outcome;
+            }
+            let action = match state.transition_kind() {
+                TransitionKind::Decision => {
+                    let n = budget(&state.player()).max(1);
+                    if last_budget.is_some_and(|last| last != n) {
+                        let root_node = self.root_node.borrow().renewed(state.clone()).into_rc();
+                        self.replace_root(root_node);
+                    }
+                    last_budget = Some(n);
+                    self.search(n)
+                }
+                TransitionKind::Chance => {
+                    sample_weighted(state.chance_outcomes(), &mut **self.rng.borrow_mut())
+                }
+            };
+            let action = action.expect(NO_POSSIBLE_ACTIONS);
+            on_move(&state, &action);
+            self.renew(&action)
+                .expect("the action chosen by the search is available");
+        }
+    }
+
+    /// The number of simulations run from the current root, since the tree was created, renewed or deserialized,
+    /// or since [`SearchTree::reset_statistics`].
+    pub fn simulations_done(&self) -> u32 {
+        self.simulations.get()
+    }
+
+    /// Zero the statistics of every node, i.e. the rewards, visits, availabilities and AMAF statistics,
+    /// and the count of [`SearchTree::simulations_done`], while keeping the nodes, their priors and the proofs of the solver.
+    pub fn reset_statistics(&mut self) {
+        let mut visited = HashSet::new();
+        let mut nodes = vec![self.root_node.clone()];
+        while let Some(node) = nodes.pop() {
+            let node = node.borrow();
+            node.zero_statistics();
+            if visited.insert(Rc::as_ptr(&node.child_nodes)) {
+                nodes.extend(node.child_nodes.borrow().iter().cloned());
+            }
+        }
+        self.simulations.set(0);
+    }
+
+    /// Take the statistics of every node, i.e. the rewards, visits, availabilities and AMAF statistics,
+    /// and the count of [`SearchTree::simulations_done`], to put them back with [`SearchTree::restore_statistics`],
+    /// e.g. to compare several policies or leaf evaluations continuing from exactly the same tree.
+    pub fn snapshot_statistics(&self) -> StatsSnapshot {
+        let mut nodes = vec![];
+        let mut visited = HashSet::new();
+        let mut stack = vec![self.root_node.clone()];
+        while let Some(node) = stack.pop() {
+            let node = node.borrow();
+            let children = visited.insert(Rc::as_ptr(&node.child_nodes)).then(|| {
+                let child_nodes = node.child_nodes.borrow();
+                stack.extend(child_nodes.iter().cloned());
+                child_nodes.len()
+            });
+            nodes.push(NodeStatistics {
+                wi: node.wi.get(),
+                ni: node.ni.get(),
+                sum_sq: node.sum_sq.get(),
+                availability: node.availability.get(),
+                amaf_wi: node.amaf_wi.get(),
+                amaf_ni: node.amaf_ni.get(),
+                reward_range: node.reward_range.get(),
+                mover_rewards: node.mover_rewards.borrow().clone(),
+                backed_up: node.backed_up.get(),
+                children,
+            });
+        }
+        StatsSnapshot {
+            nodes,
+            simulations: self.simulations.get(),
+        }
+    }
+
+    /// Put back the statistics taken by [`SearchTree::snapshot_statistics`], matching the nodes by their position in the tree.
+    /// The nodes added since, e.g. by further simulations, are kept with zero statistics, like after
+    /// [`SearchTree::reset_statistics`], so they are visited again as new leaves. The tree must still have every node
+    /// of the snapshot, which it doesn't after [`SearchTree::renew`] or pruning; then nothing is restored.
+    /// The outcomes of [`SearchTree::with_implicit_minimax`] aren't part of the snapshot: they are cleared
+    /// and backed up again as the nodes are visited.
+    pub fn restore_statistics(&self, snapshot: &StatsSnapshot) -> Result<(), RestoreError> {
+        // Match the nodes before touching any, so that a failed restore leaves the tree as it was
+        let mut matched = vec![];
+        let mut added = vec![];
+        let mut visited = HashSet::new();
+        let mut stack = vec![self.root_node.clone()];
+        let mut statistics = snapshot.nodes.iter();
+        while let Some(node) = stack.pop() {
+            let node_statistics = statistics.next().ok_or(RestoreError::StructureChanged)?;
+            {
+                let node = node.borrow();
+                let first = visited.insert(Rc::as_ptr(&node.child_nodes));
+                match (first, node_statistics.children) {
+                    (true, Some(children)) => {
+                        let child_nodes = node.child_nodes.borrow();
+                        if child_nodes.len() < children {
+                            return Err(RestoreError::StructureChanged);
+                        }
+                        stack.extend(child_nodes[..children].iter().cloned());
+                        added.extend(child_nodes[children..].iter().cloned());
+                    }
+                    (false, None) => {}
+                    _ => return Err(RestoreError::StructureChanged),
+                }
+            }
+            matched.push((node, node_statistics));
+        }
+        if statistics.next().is_some() {
+            return Err(RestoreError::StructureChanged);
+        }
+
+        for (node, statistics) in matched {
+            let node = node.borrow();
+            node.wi.set(statistics.wi);
+            node.ni.set(statistics.ni);
+            node.sum_sq.set(statistics.sum_sq);
+            node.availability.set(statistics.availability);
+            node.amaf_wi.set(statistics.amaf_wi);
+            node.amaf_ni.set(statistics.amaf_ni);
+            node.reward_range.set(statistics.reward_range);
+            *node.mover_rewards.borrow_mut() = statistics.mover_rewards.clone();
+            node.backed_up.set(statistics.backed_up);
+            node.minimax_outcome.take();
+        }
+        // The subtrees of the added nodes, except for the children they share with the nodes of the snapshot
+        while let Some(node) = added.pop() {
+            let node = node.borrow();
+            node.zero_statistics();
+            if visited.insert(Rc::as_ptr(&node.child_nodes)) {
+                added.extend(node.child_nodes.borrow().iter().cloned());
+            }
+        }
+        self.simulations.set(snapshot.simulations);
+        Ok(())
+    }
+
+    /// The number of nodes in the tree. Subtrees shared through the transposition table are counted once.
+    pub fn node_count(&self) -> usize {
+        self.nodes.get()
+    }
+
+    /// A rough estimate of the memory used by the tree: the size of a node, its state and its entry in the children
+    /// of its parent, times the number of nodes. Memory owned by the states themselves, e.g. in a `Vec`, is not included.
+    pub fn estimated_memory_bytes(&self) -> usize {
+        let node = core::mem::size_of::<RefCell<Node<G>>>() + 2 * core::mem::size_of::<usize>();
+        let state = core::mem::size_of::<G>() + 2 * core::mem::size_of::<usize>();
+        let entry = core::mem::size_of::<RcNode<G>>();
+        self.node_count() * (node + state + entry)
+    }
+
+    /// Get the current game state.
+    pub fn get_game_state(&self) -> Rc<G> {
+        self.root_node.borrow().state.clone()
+    }
+
+    /// A handle to the statistics of the root that can be read from another task or thread while this tree searches,
+    /// see [`StatsHandle`]. The handle is created on the first call, with the current statistics, and the tree publishes
+    /// its statistics to it after every call that runs simulations from then on; every call returns a clone of it.
+    /// A copy of the tree by `clone` doesn't publish to the handles of the original. Needs the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn stats_handle(&self) -> StatsHandle<G::Action> {
+        self.stats_handle
+            .get_or_init(|| StatsHandle::new(self.root_stats()))
+            .clone()
+    }
+
+    /// Get the root node.
+    pub fn root_node(&self) -> RcNode<G> {
+        self.root_node.clone()
+    }
+
+    /// Get the child of the root reached by `action`, or `None` if it hasn't been expanded, e.g. to inspect the statistics
+    /// of a candidate line. Follow the line further with [`Node::child`].
+    pub fn child_for(&self, action: &G::Action) -> Option<RcNode<G>> {
+        self.root_node.borrow().find_child(action)
+    }
+
+    /// Iterate over the nodes of the tree in breadth-first order, e.g. for analyses the tree has no method for: the root first,
+    /// then each level of the tree in turn, the children of a node in the order of [`GameState::order_actions`].
+    /// Like [`SearchTree::node_count`], a subtree shared through the transposition table is visited once, below the first
+    /// of its parents reached.
+    /// The iterator holds the nodes it has yet to visit without borrowing any of them, so the nodes it returns can be
+    /// borrowed freely; the children of a node are the ones it has when the node is returned.
+    pub fn iter_nodes(&self) -> impl Iterator<Item = RcNode<G>> {
+        let mut visited = HashSet::new();
+        let mut nodes = VecDeque::from([self.root_node.clone()]);
+        core::iter::from_fn(move || {
+            let node = nodes.pop_front()?;
+            {
+                let node = node.borrow();
+                if visited.insert(Rc::as_ptr(&node.child_nodes)) {
+                    nodes.extend(node.child_nodes.borrow().iter().cloned());
+                }
+            }
+            Some(node)
+        })
+    }
+}
+
+impl<G> SearchTree<G>
+where
+    G: GameState + Transposable + 'static,
+{
+    /// Enable the transposition table, so that the positions reached by different sequences of actions, as told by
+    /// [`Transposable::transposition_key`], share their statistics. When a node is expanded and one of its children
+    /// reaches a position that is already in the tree, the child shares the subtree of that position instead of growing
+    /// its own; the statistics of the child itself are kept per parent, so that each parent still selects among its own
+    /// children's visits.
+    ///
+    /// Beware that the tree becomes a graph:
+    /// - The visits of a shared subtree are counted once for every path leading to it, so the `ni` of its children can add
+    ///   up to more than the `ni` of the node being selected from; the tree policy is then given their sum as `np`.
+    ///   The values of a shared subtree mix the simulations of all its parents, which may have reached it with different
+    ///   intentions, and a node's own `wi` and `ni` only count the simulations that went through it.
+    /// - A position that can be reached from itself, e.g. by repeating moves, makes a cycle of `Rc`s that is never freed.
+    ///   Include whatever breaks the repetition, like a move counter, in the key of such games.
+    ///
+    /// With [`SearchTree::with_ismcts`], the children are keyed by the state of the world they were created in, so the
+    /// key should only depend on what the player to move can observe, like its own hand and the cards played: the
+    /// determinizations reaching the same decision state then share its statistics, however their hidden information
+    /// differs.
+    pub fn with_transposition_table(mut self) -> Self {
+        let transpositions = TranspositionTable::new();
+        transpositions.share(&self.root_node);
+        self.transpositions = Some(Rc::new(transpositions));
+        self
+    }
+}
+
+#[cfg(feature = "action-index")]
+impl<G> SearchTree<G>
+where
+    G: GameState,
+    G::Action: Hash,
+{
+    /// Index the children of each node by the hash of their action, so that finding the child of an action, e.g. in
+    /// [`SearchTree::renew`] or [`SearchTree::child_for`], takes constant time instead of a scan of the children, for
+    /// games with many actions. The index is built for every node already in the tree, e.g. after deserializing it,
+    /// which doesn't keep it, and the nodes expanded later inherit it.
+    pub fn with_action_index(self) -> Self {
+        let mut nodes = vec![self.root_node.clone()];
+        // The lists of children already indexed, which may be shared by the transposition table
+        let mut indexed = HashSet::new();
+        while let Some(node) = nodes.pop() {
+            let mut node = node.borrow_mut();
+            node.hash_action = Some(hash_action::<G::Action>);
+            if indexed.insert(Rc::as_ptr(&node.child_nodes)) {
+                node.index_children();
+                nodes.extend(node.child_nodes.borrow().iter().cloned());
+            }
+        }
+        self
+    }
+}
+
+/// The hash of an action in the index of [`SearchTree::with_action_index`]
+#[cfg(feature = "action-index")]
+fn hash_action<A: Hash>(action: &A) -> u64 {
+    use core::hash::Hasher;
+
+    #[cfg(feature = "std")]
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    #[cfg(not(feature = "std"))]
+    let mut hasher = Fnv1a(0xcbf2_9ce4_8422_2325);
+    action.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The FNV-1a hash, for [`hash_action`] without the standard library, whose `DefaultHasher` it replaces: the hash has
+/// to be the same on every call, which rules out the randomly seeded hashers of `hashbrown`
+#[cfg(all(feature = "action-index", not(feature = "std")))]
+struct Fnv1a(u64);
+
+#[cfg(all(feature = "action-index", not(feature = "std")))]
+impl core::hash::Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+impl<G> SearchTree<G>
+where
+    G: GameState,
+    G::Player: PartialEq,
+{
+    /// Record the all-moves-as-first (AMAF) statistics used by tree policies like [`rave`] and [`grave`]: after each
+    /// simulation, every child of a node on the path whose action the player to move at the node took at any later point
+    /// of the simulation is updated as if it had been selected. The decisions of rollouts are included if the rollout policy records them
+    /// in [`RolloutPolicy::rollout_recorded`], as [`RandomRollout`] does.
+    pub fn with_amaf(mut self, amaf: bool) -> Self {
+        self.amaf = amaf.then_some(G::Player::eq);
+        self
+    }
+
+    /// Set how the value of a node is backed up. By default, it is [`Backup::Mean`], the mean reward `wi / ni`.
+    /// With [`Backup::PowerMean`], every node on the path of a simulation recomputes its value from the values of its
+    /// children, which interpolates between the mean and the minimax value; chance states still take the mean.
+    /// Rewards should lie in `[0, 1]`, and a node's value is taken from the power mean of the player to move there by
+    /// assuming the game is zero-sum. The tree policy is given `wi` as the value times `ni`, while `wi` itself, the mean
+    /// value of a node and the final selection are unchanged.
+    pub fn with_backup(mut self, backup: Backup) -> Self {
+        self.backup = match backup {
+            Backup::Mean => None,
+            Backup::PowerMean(p) => Some((p, G::Player::eq)),
+        };
+        self
+    }
+}
+
+impl<G> SearchTree<G>
+where
+    G: GameState + StepReward<G::Action>,
+    G::Player: PartialEq,
+{
+    /// Collect the rewards of [`StepReward::step_reward`] along the path of each simulation: every node is rewarded with
+    /// the step rewards of the player who took its last action, from that action on, on top of the reward of the outcome.
+    /// They are discounted like the outcome by [`SearchTree::with_discount`].
+    ///
+    /// The steps of a rollout are not seen by the search; a rollout policy that needs them can fold them into the outcome
+    /// it returns. The solver of [`SearchTree::with_solver`] only considers the end statuses, so it should not be combined with step rewards.
+    pub fn with_step_rewards(mut self) -> Self {
+        self.step_rewards = Some((G::step_reward, G::Player::eq));
+        self
+    }
+}
+
+impl<G> SearchTree<G>
+where
+    G: GameState + SimultaneousMoves<G::Player, G::Action>,
+{
+    /// Search games where players move at the same time by decoupled UCT (DUCT): at a state with several
+    /// [`SimultaneousMoves::movers`], each of them selects their own move by the tree policy, from their own statistics
+    /// of the move summed over the joint actions containing it, and the joint action of the selected moves is taken.
+    /// Without it, the joint actions are searched as if the player of [`GameState::player`] chose them all alone.
+    ///
+    /// The tree still counts the visits of the joint actions, so [`SearchTree::best_action`] is the most visited joint action,
+    /// whose move of the player to move is the one to play. The solver of [`SearchTree::with_solver`] assumes turns and is
+    /// turned off, ISMCTS nodes search the joint actions as usual, and step rewards are not added to the movers' statistics.
+    pub fn with_simultaneous_moves(mut self) -> Self {
+        self.simultaneous = Some((G::movers, same_move::<G::Player, G, G::Action>));
+        self
+    }
+}
+
+impl<G> SearchTree<G>
+where
+    G: GameState,
+    G::Action: Debug,
+{
+    /// A compact overview of the search for printing after each move, e.g. `println!("{}", tree.summary())`:
+    /// the number of nodes and simulations, the best action with its mean value, and the most visited actions of the root
+    /// with their visits and mean values, like [`SearchTree::top_k_actions`] gives them.
+    pub fn summary(&self) -> String {
+        let action_values = self.root_action_values();
+        let best = match self.best_action() {
+            Some(action) => {
+                let value = action_values
+                    .iter()
+                    .find(|(other, _, _)| *other == action)
+                    .map_or(0., |(_, value, _)| *value);
+                format!("{action:?}, value {value:.3}")
+            }
+            None => "none".to_string(),
+        };
+        let mut lines = vec![
+            format!(
+                "nodes: {}, simulations: {}",
+                self.node_count(),
+                self.simulations_done()
+            ),
+            format!("best action: {best}"),
+        ];
+        for (action, value, ni) in self.top_k_actions(SUMMARY_ACTIONS) {
+            lines.push(format!("  {action:?}: visits {ni}, value {value:.3}"));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Search for the best action at `state` by running `n` simulations on a new [`SearchTree`] with the default settings,
+/// i.e. the [`uct`] tree policy. It is a shorthand for `SearchTree::new(state).search(n)` when only the move is needed;
+/// to reuse the tree for the next move or to configure the search, build a [`SearchTree`] instead.
+pub fn search<G: GameState>(state: Rc<G>, n: u32) -> Option<G::Action> {
+    SearchTree::new(state).search(n)
+}
+
+/// Search for the best action at `state` like [`search`], but for `duration` instead of a number of simulations.
+/// At least one simulation is run, and the time is checked after every simulation, so the search may overrun `duration`
+/// by the time of one simulation. Requires the `time` feature, which is enabled by default.
+#[cfg(feature = "time")]
+pub fn search_for<G: GameState>(state: Rc<G>, duration: Duration) -> Option<G::Action> {
+    let search_tree = SearchTree::new(state);
+    let start = Instant::now();
+    loop {
+        search_tree.step();
+        if start.elapsed() >= duration {
+            break search_tree.best_action();
+        }
     }
 }
 
-fn uct(wi: f32, ni: f32, np: f32) -> f32 {
-    wi / ni + 2_f32.sqrt() * (np.ln() / ni).sqrt()
+/// Play the game from `state` to its end with a new [`SearchTree`] with the default settings, running `per_move`
+/// simulations for every move, and return the end status. See [`SearchTree::play_out`] to configure the search,
+/// give the players different budgets, or observe the moves.
+pub fn play_game<G: GameState>(state: Rc<G>, per_move: u32) -> G::EndStatus {
+    SearchTree::new(state).play_out(|_| per_move, |_, _| {})
 }
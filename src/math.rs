@@ -0,0 +1,73 @@
+//! The float functions of the search without the standard library, which are inherent methods of `f32` and `f64`
+//! only with `std`. The trait gives the same methods from `libm`, so the code calls them the same way either way.
+//! Test builds link `std` for the harness, whose inherent methods then take precedence, so they leave the trait out.
+
+pub(crate) trait FloatMath {
+    fn ceil(self) -> Self;
+    fn cos(self) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn powf(self, n: Self) -> Self;
+    fn powi(self, n: i32) -> Self;
+    fn sqrt(self) -> Self;
+}
+
+impl FloatMath for f32 {
+    fn ceil(self) -> Self {
+        libm::ceilf(self)
+    }
+
+    fn cos(self) -> Self {
+        libm::cosf(self)
+    }
+
+    fn exp(self) -> Self {
+        libm::expf(self)
+    }
+
+    fn ln(self) -> Self {
+        libm::logf(self)
+    }
+
+    fn powf(self, n: Self) -> Self {
+        libm::powf(self, n)
+    }
+
+    fn powi(self, n: i32) -> Self {
+        libm::powf(self, n as Self)
+    }
+
+    fn sqrt(self) -> Self {
+        libm::sqrtf(self)
+    }
+}
+
+impl FloatMath for f64 {
+    fn ceil(self) -> Self {
+        libm::ceil(self)
+    }
+
+    fn cos(self) -> Self {
+        libm::cos(self)
+    }
+
+    fn exp(self) -> Self {
+        libm::exp(self)
+    }
+
+    fn ln(self) -> Self {
+        libm::log(self)
+    }
+
+    fn powf(self, n: Self) -> Self {
+        libm::pow(self, n)
+    }
+
+    fn powi(self, n: i32) -> Self {
+        libm::pow(self, n.into())
+    }
+
+    fn sqrt(self) -> Self {
+        libm::sqrt(self)
+    }
+}
@@ -0,0 +1,732 @@
+//! A thread-safe search tree, enabled by the `parallel` feature.
+
+use std::{
+    fmt::Debug,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, OnceLock, RwLock,
+    },
+    thread::JoinHandle,
+};
+
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+
+use crate::{
+    actions_of, argmax, end_status_of, priors_of, sample_weighted, uct, ChildStats, Evaluator,
+    Float, GameState, PlayerRewards, RenewError, RolloutPolicy, SearchTree, TransitionKind,
+    TreePolicy, NO_POSSIBLE_ACTIONS,
+};
+
+type ArcNode<G> = Arc<ParallelNode<G>>;
+
+type SyncPriorsFn<G, A> = dyn Fn(&G) -> Vec<(A, f32)> + Send + Sync;
+
+/// The atomic integer holding the bits of a [`Float`]
+#[cfg(not(feature = "precision64"))]
+type AtomicBits = std::sync::atomic::AtomicU32;
+#[cfg(feature = "precision64")]
+type AtomicBits = std::sync::atomic::AtomicU64;
+
+/// A [`Float`] that can be updated from several threads at once
+struct AtomicFloat(AtomicBits);
+
+impl AtomicFloat {
+    fn new(value: Float) -> Self {
+        AtomicFloat(AtomicBits::new(value.to_bits()))
+    }
+
+    fn get(&self) -> Float {
+        Float::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    fn add(&self, value: Float) {
+        let _ = self
+            .0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                Some((Float::from_bits(bits) + value).to_bits())
+            });
+    }
+}
+
+impl Debug for AtomicFloat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+enum LeafEvaluation<G: GameState> {
+    Expand,
+    Rollout(Arc<dyn RolloutPolicy<G> + Send + Sync>),
+    Evaluate(Arc<dyn Evaluator<G> + Send + Sync>),
+}
+
+impl<G: GameState> Clone for LeafEvaluation<G> {
+    fn clone(&self) -> Self {
+        match self {
+            LeafEvaluation::Expand => LeafEvaluation::Expand,
+            LeafEvaluation::Rollout(rollout_policy) => {
+                LeafEvaluation::Rollout(rollout_policy.clone())
+            }
+            LeafEvaluation::Evaluate(evaluator) => LeafEvaluation::Evaluate(evaluator.clone()),
+        }
+    }
+}
+
+/// A background thread searching the tree, see [`ParallelSearchTree::start_pondering`].
+struct Pondering {
+    stop: Arc<AtomicBool>,
+    /// the thread, which returns the number of simulations it ran
+    worker: JoinHandle<u32>,
+}
+
+impl Pondering {
+    fn stop(self) -> u32 {
+        self.stop.store(true, Ordering::Relaxed);
+        self.worker.join().expect("the pondering thread panicked")
+    }
+}
+
+/// The settings of the search tree that are passed down the tree during a simulation.
+struct SearchContext<'a, G: GameState> {
+    leaf_evaluation: &'a LeafEvaluation<G>,
+    priors: Option<&'a SyncPriorsFn<G, G::Action>>,
+    virtual_loss: Float,
+    rng: &'a mut dyn RngCore,
+}
+
+/// The thread-safe counterpart of [`crate::Node`], whose statistics are updated atomically.
+pub struct ParallelNode<G: GameState> {
+    state: Arc<G>,
+    last_action: Option<G::Action>,
+    child_nodes: RwLock<Vec<ArcNode<G>>>,
+    /// hash of the actions indexing the children, once [`ParallelSearchTree::with_action_index`] enabled the index
+    #[cfg(feature = "action-index")]
+    hash_action: OnceLock<fn(&G::Action) -> u64>,
+    /// index of the first child in `child_nodes` with each hash of its last action, written while holding the lock of
+    /// `child_nodes`
+    #[cfg(feature = "action-index")]
+    child_index: RwLock<std::collections::HashMap<u64, usize>>,
+    /// cached result of `state.end_status()`
+    end_status: OnceLock<Option<G::EndStatus>>,
+
+    /// times of win
+    wi: AtomicFloat,
+    /// times of selection
+    ni: AtomicFloat,
+    /// sum of the squares of the rewards counted in `wi`
+    sum_sq: AtomicFloat,
+    /// virtual losses of the simulations currently passing through the node
+    virtual_loss: AtomicFloat,
+
+    /// prior probability of the last action, or its probability if the parent is a chance state
+    prior: f32,
+
+    /// policy used to select the child node, locked after `child_nodes` when both are
+    tree_policy: RwLock<Arc<dyn TreePolicy + Send + Sync>>,
+}
+
+impl<G> Debug for ParallelNode<G>
+where
+    G: GameState + Debug,
+    G::EndStatus: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParallelNode")
+            .field("state", &self.state)
+            .field("wi", &self.wi)
+            .field("ni", &self.ni)
+            .finish()
+    }
+}
+
+impl<G: GameState> Drop for ParallelNode<G> {
+    fn drop(&mut self) {
+        // Drop the subtree iteratively, so that dropping a deep tree can't overflow the stack either
+        let mut child_nodes = std::mem::take(self.child_nodes.get_mut().unwrap());
+        while let Some(node) = child_nodes.pop() {
+            if let Ok(mut node) = Arc::try_unwrap(node) {
+                child_nodes.append(node.child_nodes.get_mut().unwrap());
+            }
+        }
+    }
+}
+
+impl<G: GameState> ParallelNode<G> {
+    fn new(state: Arc<G>, tree_policy: Arc<dyn TreePolicy + Send + Sync>) -> Self {
+        ParallelNode {
+            state,
+            last_action: None,
+            child_nodes: RwLock::new(vec![]),
+            #[cfg(feature = "action-index")]
+            hash_action: OnceLock::new(),
+            #[cfg(feature = "action-index")]
+            child_index: RwLock::default(),
+            end_status: OnceLock::new(),
+            wi: AtomicFloat::new(0.),
+            ni: AtomicFloat::new(0.),
+            sum_sq: AtomicFloat::new(0.),
+            virtual_loss: AtomicFloat::new(0.),
+            prior: 1.,
+            tree_policy: RwLock::new(tree_policy),
+        }
+    }
+
+    fn derive_child(&self, action: G::Action, prior: f32) -> ArcNode<G> {
+        Arc::new(ParallelNode {
+            state: Arc::new(self.state.act(&action)),
+            last_action: Some(action),
+            child_nodes: RwLock::new(vec![]),
+            #[cfg(feature = "action-index")]
+            hash_action: self.hash_action.clone(),
+            #[cfg(feature = "action-index")]
+            child_index: RwLock::default(),
+            end_status: OnceLock::new(),
+            wi: AtomicFloat::new(0.),
+            ni: AtomicFloat::new(0.),
+            sum_sq: AtomicFloat::new(0.),
+            virtual_loss: AtomicFloat::new(0.),
+            prior,
+            tree_policy: RwLock::new(self.tree_policy.read().unwrap().clone()),
+        })
+    }
+
+    /// The end status of the node's state, which is only computed once
+    fn end_status(&self) -> Option<&G::EndStatus> {
+        self.end_status
+            .get_or_init(|| end_status_of(self.state.as_ref()))
+            .as_ref()
+    }
+
+    fn find_child(&self, action: &G::Action) -> Option<ArcNode<G>> {
+        #[cfg(feature = "action-index")]
+        if let Some(hash_action) = self.hash_action.get() {
+            let index = self
+                .child_index
+                .read()
+                .unwrap()
+                .get(&hash_action(action))
+                .copied();
+            let node = index.and_then(|index| self.child_nodes.read().unwrap().get(index).cloned());
+            if let Some(node) = node.filter(|node| node.last_action.as_ref() == Some(action)) {
+                return Some(node);
+            }
+            // Another action with the same hash, or a child of a node whose index is being built by another thread,
+            // which only the scan below finds
+        }
+        for node in self.child_nodes.read().unwrap().iter() {
+            if node.last_action.as_ref() == Some(action) {
+                return Some(node.clone());
+            }
+        }
+        None
+    }
+
+    /// Times of selection including the virtual losses, which count as visits rewarded 0
+    fn ni_with_virtual_loss(&self) -> Float {
+        self.ni.get() + self.virtual_loss.get()
+    }
+
+    fn select(&self, depth: u32) -> Option<ArcNode<G>> {
+        let child_nodes = self.child_nodes.read().unwrap();
+        let unvisited: Vec<_> = child_nodes
+            .iter()
+            .filter(|node| node.ni_with_virtual_loss() == 0.)
+            .map(|node| (node.clone(), 0.))
+            .collect();
+        if !unvisited.is_empty() {
+            return argmax(unvisited, None);
+        }
+
+        let tree_policy = self.tree_policy.read().unwrap().clone();
+        let scored = child_nodes
+            .iter()
+            .map(|node| {
+                let value = tree_policy.score(&ChildStats {
+                    wi: node.wi.get(),
+                    ni: node.ni_with_virtual_loss(),
+                    sum_sq: node.sum_sq.get(),
+                    np: self.ni_with_virtual_loss(),
+                    prior: Float::from(node.prior),
+                    amaf_wi: 0.,
+                    amaf_ni: 0.,
+                    ref_amaf_wi: 0.,
+                    ref_amaf_ni: 0.,
+                    minimax_value: node.wi.get() / node.ni_with_virtual_loss(),
+                    min_reward: 0.,
+                    max_reward: 1.,
+                    depth,
+                });
+                (node.clone(), value)
+            })
+            .collect();
+        argmax(scored, None)
+    }
+
+    fn sample_chance_outcome(&self, rng: &mut dyn RngCore) -> Option<ArcNode<G>> {
+        let weighted = self
+            .child_nodes
+            .read()
+            .unwrap()
+            .iter()
+            .map(|node| (node.clone(), node.prior))
+            .collect();
+        sample_weighted(weighted, rng)
+    }
+
+    fn select_most_visited(&self) -> Option<ArcNode<G>> {
+        let scored = self
+            .child_nodes
+            .read()
+            .unwrap()
+            .iter()
+            .map(|node| (node.clone(), node.ni.get()))
+            .collect();
+        argmax(scored, None)
+    }
+
+    /// Expand all possible actions, or the chance outcomes of a chance state. Only one thread expands a node;
+    /// the others wait for it to finish.
+    fn expand(&self, priors: Option<&SyncPriorsFn<G, G::Action>>) {
+        if self.is_expanded() {
+            return;
+        }
+        let mut child_nodes = self.child_nodes.write().unwrap();
+        if !child_nodes.is_empty() {
+            return;
+        }
+        if self.state.transition_kind() == TransitionKind::Chance {
+            for (action, probability) in self.state.chance_outcomes() {
+                child_nodes.push(self.derive_child(action, probability));
+            }
+            #[cfg(feature = "action-index")]
+            self.index_children(&child_nodes);
+            return;
+        }
+        let actions = actions_of(self.state.as_ref());
+        let priors = priors.map(|priors| priors as &crate::PriorsFn<'_, G, G::Action>);
+        let priors = priors_of(self.state.as_ref(), &actions, priors);
+        for (action, prior) in actions.into_iter().zip(priors) {
+            child_nodes.push(self.derive_child(action, prior));
+        }
+        #[cfg(feature = "action-index")]
+        self.index_children(&child_nodes);
+    }
+
+    /// Index the children by action once they are expanded, if the node has an index.
+    #[cfg(feature = "action-index")]
+    fn index_children(&self, child_nodes: &[ArcNode<G>]) {
+        let Some(hash_action) = self.hash_action.get() else {
+            return;
+        };
+        let mut child_index = self.child_index.write().unwrap();
+        child_index.clear();
+        for (index, node) in child_nodes.iter().enumerate() {
+            if let Some(action) = &node.last_action {
+                child_index.entry(hash_action(action)).or_insert(index);
+            }
+        }
+    }
+
+    fn is_expanded(&self) -> bool {
+        !self.child_nodes.read().unwrap().is_empty()
+    }
+
+    fn backpropagate(&self, reward: f32) {
+        let reward = Float::from(reward);
+        self.ni.add(1.);
+        self.wi.add(reward);
+        self.sum_sq.add(reward * reward);
+    }
+
+    /// Simulate a game play from `root_node` iteratively, like [`crate::Node`] does. The virtual loss is added to each node
+    /// when it is pushed to the path and removed when it is backpropagated.
+    fn simulate(root_node: &ArcNode<G>, ctx: &mut SearchContext<G>) -> G::EndStatus {
+        let mut path = vec![(
+            root_node.clone(),
+            root_node.state.player(),
+            root_node.state.current_player_index(),
+        )];
+        let outcome = loop {
+            let node = path.last().unwrap().0.clone();
+            if let Some(outcome) = node.end_status() {
+                break outcome.clone();
+            }
+            match ctx.leaf_evaluation {
+                LeafEvaluation::Rollout(rollout_policy) if node.ni.get() == 0. => {
+                    break rollout_policy.rollout(&node.state, ctx.rng);
+                }
+                LeafEvaluation::Evaluate(evaluator) if node.ni.get() == 0. => {
+                    break evaluator.evaluate(&node.state);
+                }
+                _ => {}
+            }
+
+            node.expand(ctx.priors);
+            let selected_node = match node.state.transition_kind() {
+                TransitionKind::Decision => node.select(path.len() as u32 - 1),
+                TransitionKind::Chance => node.sample_chance_outcome(ctx.rng),
+            };
+            let selected_node = selected_node.expect(NO_POSSIBLE_ACTIONS);
+            selected_node.virtual_loss.add(ctx.virtual_loss);
+            path.push((
+                selected_node,
+                node.state.player(),
+                node.state.current_player_index(),
+            ));
+        };
+
+        let mut rewards = PlayerRewards::new(root_node.state.num_players());
+        for (i, (node, player, index)) in path.iter().enumerate().rev() {
+            node.backpropagate(rewards.reward_of(player, *index, &outcome));
+            if i > 0 {
+                node.virtual_loss.add(-ctx.virtual_loss);
+            }
+        }
+        outcome
+    }
+
+    pub fn state(&self) -> Arc<G> {
+        self.state.clone()
+    }
+
+    pub fn child_nodes(&self) -> Vec<ArcNode<G>> {
+        self.child_nodes.read().unwrap().clone()
+    }
+
+    pub fn wi(&self) -> Float {
+        self.wi.get()
+    }
+
+    pub fn ni(&self) -> Float {
+        self.ni.get()
+    }
+
+    pub fn prior(&self) -> f32 {
+        self.prior
+    }
+}
+
+/// [`ParallelSearchTree`] is a `Send + Sync` search tree whose simulations can run on several threads against the shared tree.
+/// It uses `Arc` and atomics in place of the `Rc` and `Cell`s of [`crate::SearchTree`], so the game state, the end status and the actions
+/// need to be `Send + Sync`, and so do the configured policies.
+///
+/// Either call [`ParallelSearchTree::search`] from your own threads, or let [`ParallelSearchTree::search_parallel`] spawn them.
+pub struct ParallelSearchTree<G>
+where
+    G: GameState + Send + Sync,
+    G::EndStatus: Send + Sync,
+    G::Action: Send + Sync,
+{
+    root_node: ArcNode<G>,
+    leaf_evaluation: LeafEvaluation<G>,
+    priors: Option<Arc<SyncPriorsFn<G, G::Action>>>,
+    virtual_loss: Float,
+    rng: Mutex<Box<dyn RngCore + Send>>,
+    pondering: Option<Pondering>,
+}
+
+impl<G> ParallelSearchTree<G>
+where
+    G: GameState + Send + Sync,
+    G::EndStatus: Send + Sync,
+    G::Action: Send + Sync,
+{
+    /// Create a new search tree.
+    pub fn new(game_state: Arc<G>) -> Self {
+        ParallelSearchTree {
+            root_node: Arc::new(ParallelNode::new(game_state, Arc::new(uct))),
+            leaf_evaluation: LeafEvaluation::Expand,
+            priors: None,
+            virtual_loss: 0.,
+            rng: Mutex::new(Box::new(StdRng::seed_from_u64(0))),
+            pondering: None,
+        }
+    }
+
+    /// Set the tree policy. By default, it is UCT tree policy. The policy is set on every node already in the tree,
+    /// see [`ParallelSearchTree::set_tree_policy`].
+    pub fn with_tree_policy(self, tree_policy: impl TreePolicy + Send + Sync + 'static) -> Self {
+        self.set_tree_policy(tree_policy);
+        self
+    }
+
+    /// Change the tree policy of a tree in use, like [`crate::SearchTree::set_tree_policy`]: the policy replaces the old
+    /// one on every node already in the tree, and is inherited by the nodes expanded later. It may be called while other
+    /// threads search or ponder, and while the root is held by [`ParallelSearchTree::root_node`]; their selections switch
+    /// to the new policy node by node as it is set.
+    pub fn set_tree_policy(&self, tree_policy: impl TreePolicy + Send + Sync + 'static) {
+        let tree_policy: Arc<dyn TreePolicy + Send + Sync> = Arc::new(tree_policy);
+        let mut nodes = vec![self.root_node.clone()];
+        while let Some(node) = nodes.pop() {
+            // Holding the children, so that a node being expanded gets the new policy with its children
+            let child_nodes = node.child_nodes.read().unwrap();
+            *node.tree_policy.write().unwrap() = tree_policy.clone();
+            nodes.extend(child_nodes.iter().cloned());
+        }
+    }
+
+    /// Index the children of each node by the hash of their action, like [`crate::SearchTree::with_action_index`].
+    /// The index is built for every node already in the tree, and the nodes expanded later inherit it.
+    #[cfg(feature = "action-index")]
+    pub fn with_action_index(self) -> Self
+    where
+        G::Action: std::hash::Hash,
+    {
+        let mut nodes = vec![self.root_node.clone()];
+        while let Some(node) = nodes.pop() {
+            // Holding the children, so that a node being expanded gets the index with its children
+            let child_nodes = node.child_nodes.read().unwrap();
+            if node
+                .hash_action
+                .set(crate::hash_action::<G::Action>)
+                .is_ok()
+            {
+                node.index_children(&child_nodes);
+                nodes.extend(child_nodes.iter().cloned());
+            }
+        }
+        self
+    }
+
+    /// Set the rollout policy used to evaluate newly reached leaves, replacing the evaluator if one was set.
+    pub fn with_rollout_policy(
+        mut self,
+        rollout_policy: impl RolloutPolicy<G> + Send + Sync + 'static,
+    ) -> Self {
+        self.leaf_evaluation = LeafEvaluation::Rollout(Arc::new(rollout_policy));
+        self
+    }
+
+    /// Set the evaluator used to evaluate newly reached leaves instead of rollouts, replacing the rollout policy if one was set.
+    pub fn with_evaluator(mut self, evaluator: impl Evaluator<G> + Send + Sync + 'static) -> Self {
+        self.leaf_evaluation = LeafEvaluation::Evaluate(Arc::new(evaluator));
+        self
+    }
+
+    /// Set the callback giving the prior probability of each possible action at a state, see [`crate::SearchTree::with_priors`].
+    pub fn with_priors(
+        mut self,
+        priors: impl Fn(&G) -> Vec<(G::Action, f32)> + Send + Sync + 'static,
+    ) -> Self {
+        self.priors = Some(Arc::new(priors));
+        self
+    }
+
+    /// Set the virtual loss, which keeps the threads from all descending into the same path.
+    /// While a simulation passes through a node, the node counts `virtual_loss` extra visits rewarded 0 in selection,
+    /// making it look worse to the other threads until the simulation is backpropagated. By default, it is 0.
+    pub fn with_virtual_loss(mut self, virtual_loss: Float) -> Self {
+        self.virtual_loss = virtual_loss;
+        self
+    }
+
+    /// Set the random number generator. Every call of [`ParallelSearchTree::search`] draws a seed from it for its own RNG,
+    /// so a search is reproducible only if the threads call it in the same order.
+    pub fn with_rng(self, rng: impl RngCore + Send + 'static) -> Self {
+        *self.rng.lock().unwrap() = Box::new(rng);
+        self
+    }
+
+    fn simulate_n(&self, n: u32) {
+        let mut rng = StdRng::seed_from_u64(self.rng.lock().unwrap().gen());
+        let mut ctx = SearchContext {
+            leaf_evaluation: &self.leaf_evaluation,
+            priors: self.priors.as_deref(),
+            virtual_loss: self.virtual_loss,
+            rng: &mut rng,
+        };
+        for _ in 0..n {
+            ParallelNode::simulate(&self.root_node, &mut ctx);
+        }
+    }
+
+    /// Run `n` simulations on the calling thread and return the best action so far.
+    /// It can be called from several threads at once, which all refine the same tree.
+    pub fn search(&self, n: u32) -> Option<G::Action> {
+        self.simulate_n(n);
+        self.best_action()
+    }
+
+    /// Split `n` simulations among `threads` scoped threads running [`ParallelSearchTree::search`], and return the best action.
+    pub fn search_parallel(&self, n: u32, threads: u32) -> Option<G::Action> {
+        let threads = threads.max(1);
+        std::thread::scope(|scope| {
+            for i in 0..threads {
+                let n = n / threads + u32::from(i < n % threads);
+                scope.spawn(move || self.simulate_n(n));
+            }
+        });
+        self.best_action()
+    }
+
+    /// The most visited action of the root.
+    pub fn best_action(&self) -> Option<G::Action> {
+        let selected_node = self.root_node.select_most_visited();
+        selected_node.and_then(|v| v.last_action.clone())
+    }
+
+    /// Move to the next state and renew the root node with given action.
+    /// Pondering is stopped first, and the simulations it ran are kept in the subtree of the action.
+    pub fn renew(&mut self, action: &G::Action) -> Result<(), RenewError> {
+        self.stop_pondering();
+        if self.root_node.end_status().is_some() {
+            return Err(RenewError::GameAlreadyEnded);
+        }
+        self.root_node.expand(self.priors.as_deref());
+        match self.root_node.find_child(action) {
+            Some(node) => {
+                self.root_node = node;
+                Ok(())
+            }
+            None => Err(RenewError::ActionNotAvailable),
+        }
+    }
+
+    /// Get the current game state.
+    pub fn get_game_state(&self) -> Arc<G> {
+        self.root_node.state.clone()
+    }
+
+    /// Get the root node.
+    pub fn root_node(&self) -> ArcNode<G> {
+        self.root_node.clone()
+    }
+
+    /// Stop the pondering started by [`ParallelSearchTree::start_pondering`], waiting for its thread to finish the
+    /// simulation it is running, and return the number of simulations it ran. It returns 0 if the tree isn't pondering.
+    pub fn stop_pondering(&mut self) -> u32 {
+        self.pondering.take().map_or(0, Pondering::stop)
+    }
+
+    /// Whether the tree is pondering, see [`ParallelSearchTree::start_pondering`].
+    pub fn is_pondering(&self) -> bool {
+        self.pondering.is_some()
+    }
+}
+
+impl<G> ParallelSearchTree<G>
+where
+    G: GameState + Send + Sync + 'static,
+    G::EndStatus: Send + Sync,
+    G::Action: Send + Sync,
+{
+    /// Start pondering: keep running simulations from the current root on a background thread, e.g. while the opponent
+    /// is thinking about their move, until [`ParallelSearchTree::stop_pondering`] or [`ParallelSearchTree::renew`] is called.
+    /// After the opponent moves, [`ParallelSearchTree::renew`] with their move keeps what was searched below it,
+    /// and the search can go on from there. [`ParallelSearchTree::search`] can be called while pondering and adds to the
+    /// same tree. The thread draws its seed from the RNG like a call of [`ParallelSearchTree::search`] does.
+    /// Calling it while the tree is already pondering does nothing.
+    pub fn start_pondering(&mut self) {
+        if self.pondering.is_some() {
+            return;
+        }
+        let root_node = self.root_node.clone();
+        let leaf_evaluation = self.leaf_evaluation.clone();
+        let priors = self.priors.clone();
+        let virtual_loss = self.virtual_loss;
+        let seed = self.rng.lock().unwrap().gen();
+        let stop = Arc::new(AtomicBool::new(false));
+        let worker = {
+            let stop = stop.clone();
+            std::thread::spawn(move || {
+                let mut rng = StdRng::seed_from_u64(seed);
+                let mut ctx = SearchContext {
+                    leaf_evaluation: &leaf_evaluation,
+                    priors: priors.as_deref(),
+                    virtual_loss,
+                    rng: &mut rng,
+                };
+                let mut simulations: u32 = 0;
+                // Simulations from an ended root would only count its outcome again
+                while !stop.load(Ordering::Relaxed) && root_node.end_status().is_none() {
+                    ParallelNode::simulate(&root_node, &mut ctx);
+                    simulations = simulations.saturating_add(1);
+                }
+                simulations
+            })
+        };
+        self.pondering = Some(Pondering { stop, worker });
+    }
+}
+
+impl<G> Drop for ParallelSearchTree<G>
+where
+    G: GameState + Send + Sync,
+    G::EndStatus: Send + Sync,
+    G::Action: Send + Sync,
+{
+    fn drop(&mut self) {
+        self.stop_pondering();
+    }
+}
+
+/// Root parallelization: build `n_trees` independent [`SearchTree`]s on as many threads, each searching `n_per_tree`
+/// simulations from `state` with its own RNG seed, then sum the statistics of the root's children per action
+/// and return the most visited action. Unlike [`ParallelSearchTree`], the trees share nothing while searching.
+///
+/// The trees break ties at random and are otherwise configured by default; see [`parallel_root_search_with`] to configure them.
+pub fn parallel_root_search<G>(state: &G, n_per_tree: u32, n_trees: u32) -> Option<G::Action>
+where
+    G: GameState + Send + Sync + Clone,
+    G::Action: Send,
+{
+    parallel_root_search_with(state, n_per_tree, n_trees, |search_tree| search_tree)
+}
+
+/// Like [`parallel_root_search`], but each tree is passed to `configure` before searching, e.g. to set a rollout policy.
+/// The trees given to `configure` have their RNG seeded with their index and random tiebreak enabled.
+pub fn parallel_root_search_with<G>(
+    state: &G,
+    n_per_tree: u32,
+    n_trees: u32,
+    configure: impl Fn(SearchTree<G>) -> SearchTree<G> + Sync,
+) -> Option<G::Action>
+where
+    G: GameState + Send + Sync + Clone,
+    G::Action: Send,
+{
+    let configure = &configure;
+    let root_stats: Vec<Vec<(G::Action, Float, Float)>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..n_trees)
+            .map(|i| {
+                scope.spawn(move || {
+                    let search_tree = SearchTree::new(Rc::new(state.clone()))
+                        .with_rng(StdRng::seed_from_u64(i.into()))
+                        .with_random_tiebreak(true);
+                    let search_tree = configure(search_tree);
+                    search_tree.search(n_per_tree);
+                    let root_node = search_tree.root_node();
+                    let child_nodes = root_node.borrow().child_nodes();
+                    child_nodes
+                        .iter()
+                        .filter_map(|node| {
+                            let node = node.borrow();
+                            Some((node.last_action.clone()?, node.wi(), node.ni()))
+                        })
+                        .collect()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+
+    let mut merged: Vec<(G::Action, Float, Float)> = vec![];
+    for (action, wi, ni) in root_stats.into_iter().flatten() {
+        match merged.iter_mut().find(|(a, _, _)| *a == action) {
+            Some((_, merged_wi, merged_ni)) => {
+                *merged_wi += wi;
+                *merged_ni += ni;
+            }
+            None => merged.push((action, wi, ni)),
+        }
+    }
+    let scored = merged
+        .into_iter()
+        .map(|(action, _, ni)| (action, ni))
+        .collect();
+    argmax(scored, None)
+}
@@ -0,0 +1,338 @@
+//! Tree policies used to select the child node to descend into during a simulation.
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::FloatMath;
+use crate::Float;
+
+/// The statistics of a child node that a [`TreePolicy`] scores during selection.
+#[derive(Debug, Clone, Copy)]
+pub struct ChildStats {
+    /// Total reward of the child node
+    pub wi: Float,
+    /// Times the child node has been selected
+    pub ni: Float,
+    /// Sum of the squares of the rewards of the child node
+    pub sum_sq: Float,
+    /// Times the parent node has been selected
+    pub np: Float,
+    /// Prior probability of the action leading to the child node, see [`crate::SearchTree::with_priors`]
+    pub prior: Float,
+    /// Total reward of the simulations that took the action of the child node at any later point by the same player,
+    /// see [`crate::SearchTree::with_amaf`]
+    pub amaf_wi: Float,
+    /// Number of the simulations counted in `amaf_wi`
+    pub amaf_ni: Float,
+    /// `amaf_wi` of the child with the same action below the reference node, the deepest node of the path with at least
+    /// [`TreePolicy::reference_visits`] visits; it is `amaf_wi` itself if the reference is the parent, see [`grave`]
+    pub ref_amaf_wi: Float,
+    /// Number of the simulations counted in `ref_amaf_wi`
+    pub ref_amaf_ni: Float,
+    /// Implicit minimax value of the child for the player to move at the parent, see
+    /// [`crate::SearchTree::with_implicit_minimax`], or its mean value `wi / ni` if it has none
+    pub minimax_value: Float,
+    /// Smallest reward of a single simulation observed among the parent's children, or 0 if there is none
+    pub min_reward: Float,
+    /// Largest reward of a single simulation observed among the parent's children, or 1 if there is none
+    pub max_reward: Float,
+    /// Depth of the parent node below the root of the search, which is at depth 0
+    pub depth: u32,
+}
+
+/// The trait for the tree policy. Among the children that have been visited, the one with the highest score is selected.
+///
+/// A score of `Float::INFINITY` always wins over finite scores, while a NaN score is treated as `Float::NEG_INFINITY`:
+/// the child is only selected if every other child scores NaN or negative infinity too, so a policy can't make the
+/// selection fail, and ties among them are broken like any other tie.
+///
+/// It is implemented for every `Fn(wi, ni, np) -> Float` closure, so simple policies like [`uct`] can be written as plain functions,
+/// and [`depth_aware`] adapts closures that also take the depth of the parent node.
+pub trait TreePolicy {
+    /// Score a child node
+    fn score(&self, child: &ChildStats) -> Float;
+
+    /// The visits from which a node on the path of a simulation becomes the reference node whose AMAF statistics are
+    /// given to the policy as [`ChildStats::ref_amaf_wi`] and [`ChildStats::ref_amaf_ni`] below it, see [`grave`].
+    /// By default there is no reference node, and they are the child's own AMAF statistics.
+    fn reference_visits(&self) -> Option<Float> {
+        None
+    }
+}
+
+impl<F> TreePolicy for F
+where
+    F: Fn(Float, Float, Float) -> Float,
+{
+    fn score(&self, child: &ChildStats) -> Float {
+        self(child.wi, child.ni, child.np)
+    }
+}
+
+/// A tree policy given by a closure that also takes the depth, see [`depth_aware`].
+#[derive(Debug, Clone, Copy)]
+pub struct DepthAware<F>(pub F);
+
+impl<F> TreePolicy for DepthAware<F>
+where
+    F: Fn(Float, Float, Float, u32) -> Float,
+{
+    fn score(&self, child: &ChildStats) -> Float {
+        (self.0)(child.wi, child.ni, child.np, child.depth)
+    }
+}
+
+/// Adapt an `Fn(wi, ni, np, depth) -> Float` closure into a tree policy, where `depth` is the depth of the parent node
+/// below the root, e.g. for an exploration constant that decays with depth so that the root explores the most:
+/// `depth_aware(|wi, ni, np, depth| uct_with_c(2. / (1. + depth as Float))(wi, ni, np))`.
+pub fn depth_aware<F>(policy: F) -> DepthAware<F>
+where
+    F: Fn(Float, Float, Float, u32) -> Float,
+{
+    DepthAware(policy)
+}
+
+/// The default UCT tree policy, i.e. [`uct_with_c`] with the exploration constant `sqrt(2)`.
+/// It assumes the rewards given by [`crate::Player::reward_when_outcome_is`] lie in `[0, 1]`.
+pub fn uct(wi: Float, ni: Float, np: Float) -> Float {
+    uct_with_c(Float::sqrt(2.))(wi, ni, np)
+}
+
+/// UCT tree policy with a custom exploration constant `c`, to be passed to [`crate::SearchTree::with_tree_policy`].
+///
+/// The constant should match the scale of the rewards: `sqrt(2)` is the theoretical choice for rewards in `[0, 1]`,
+/// and for rewards in `[a, b]` it should be scaled by `b - a`.
+/// A smaller `c` makes the search greedier and a larger one makes it explore more.
+pub fn uct_with_c(c: Float) -> impl Fn(Float, Float, Float) -> Float {
+    move |wi, ni, np| wi / ni + c * (np.ln() / ni).sqrt()
+}
+
+/// UCB1-Tuned tree policy, see [`ucb1_tuned`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ucb1Tuned;
+
+impl TreePolicy for Ucb1Tuned {
+    fn score(&self, child: &ChildStats) -> Float {
+        let mean = child.wi / child.ni;
+        let log = child.np.ln() / child.ni;
+        let variance = (child.sum_sq / child.ni - mean * mean).max(0.) + (2. * log).sqrt();
+        mean + (log * variance.min(0.25)).sqrt()
+    }
+}
+
+/// UCB1-Tuned tree policy `Q(s,a) + sqrt(ln N(s) / N(s,a) * min(1/4, V(s,a)))`, where `V(s,a)` is the variance of the child's
+/// rewards plus `sqrt(2 * ln N(s) / N(s,a))`. It explores less than [`uct`] around children whose rewards vary little,
+/// and has no exploration constant to tune.
+/// Like [`uct`], it assumes rewards in `[0, 1]`, whose variance is at most 1/4.
+pub fn ucb1_tuned() -> Ucb1Tuned {
+    Ucb1Tuned
+}
+
+/// UCB-V tree policy, see [`ucb_v`].
+#[derive(Debug, Clone, Copy)]
+pub struct UcbV {
+    /// The exploration rate, which scales `ln N(s)`
+    pub c: Float,
+    /// The bound of the rewards, e.g. 1 for rewards in `[0, 1]`
+    pub b: Float,
+}
+
+impl TreePolicy for UcbV {
+    fn score(&self, child: &ChildStats) -> Float {
+        let mean = child.wi / child.ni;
+        let variance = (child.sum_sq / child.ni - mean * mean).max(0.);
+        let exploration = self.c * child.np.ln() / child.ni;
+        mean + (2. * variance * exploration).sqrt() + 3. * self.b * exploration
+    }
+}
+
+/// UCB-V tree policy of Audibert et al. `Q(s,a) + sqrt(2 * V(s,a) * E) + 3 * b * E`, where `V(s,a)` is the variance of
+/// the child's rewards and `E = c * ln N(s) / N(s,a)`. Like [`ucb1_tuned`], it explores less around children whose
+/// rewards vary little, but its exploration rate `c` and reward bound `b` are explicit; `c = 1` and `b = 1` suit rewards
+/// in `[0, 1]`.
+pub fn ucb_v(c: Float, b: Float) -> UcbV {
+    UcbV { c, b }
+}
+
+/// SP-MCTS tree policy, see [`sp_mcts`].
+#[derive(Debug, Clone, Copy)]
+pub struct SpMcts {
+    /// The exploration constant of the UCT term
+    pub c: Float,
+    /// The constant added to the sum of the squared deviations, which keeps rarely visited children explored
+    pub d: Float,
+}
+
+impl TreePolicy for SpMcts {
+    fn score(&self, child: &ChildStats) -> Float {
+        let mean = child.wi / child.ni;
+        let deviation = ((child.sum_sq - child.ni * mean * mean).max(0.) + self.d) / child.ni;
+        mean + self.c * (child.np.ln() / child.ni).sqrt() + deviation.sqrt()
+    }
+}
+
+/// Single-player MCTS tree policy of Schadd et al. `Q(s,a) + c * sqrt(ln N(s) / N(s,a)) + sqrt((Σx² - N(s,a) * Q(s,a)² + d) / N(s,a))`,
+/// for puzzles and other single-agent optimization problems. `Σx²` is the sum of the squared rewards of the child, so
+/// the last term is its standard deviation inflated by `d`: unlike an opponent, a single player may reach the top score
+/// the rewards of a varied child hint at. Rewards may be on any scale, like puzzle scores, with `c` and `d` scaled
+/// along: `d` is on the scale of the squared rewards. Select the action to play by the best score found rather than
+/// by the visits with [`crate::FinalSelection::MaxReward`].
+pub fn sp_mcts(c: Float, d: Float) -> SpMcts {
+    SpMcts { c, d }
+}
+
+/// Implicit minimax tree policy, see [`implicit_minimax`].
+#[derive(Debug, Clone, Copy)]
+pub struct ImplicitMinimax {
+    /// The weight of the implicit minimax value against the mean value
+    pub alpha: Float,
+    /// The exploration constant of the UCT term
+    pub c: Float,
+}
+
+impl TreePolicy for ImplicitMinimax {
+    fn score(&self, child: &ChildStats) -> Float {
+        let q = child.wi / child.ni;
+        (1. - self.alpha) * q
+            + self.alpha * child.minimax_value
+            + self.c * (child.np.ln() / child.ni).sqrt()
+    }
+}
+
+/// Implicit minimax tree policy of Lanctot et al. `(1 - α) * Q(s,a) + α * V(s,a) + c * sqrt(ln N(s) / N(s,a))`, which
+/// blends the mean value `Q(s,a)` with the implicit minimax value `V(s,a)`, the heuristic evaluations backed up by
+/// minimax of [`crate::SearchTree::with_implicit_minimax`]. Without it, `V(s,a)` is the mean value and the policy is
+/// [`uct`]. The exploration constant is `sqrt(2)`; set [`ImplicitMinimax::c`] to change it. Like [`uct`], it assumes
+/// rewards in `[0, 1]`.
+pub fn implicit_minimax(alpha: Float) -> ImplicitMinimax {
+    ImplicitMinimax {
+        alpha,
+        c: Float::sqrt(2.),
+    }
+}
+
+/// UCT tree policy with normalized values, see [`uct_normalized`].
+#[derive(Debug, Clone, Copy)]
+pub struct UctNormalized {
+    /// The exploration constant, as for rewards in `[0, 1]`
+    pub c: Float,
+}
+
+impl TreePolicy for UctNormalized {
+    fn score(&self, child: &ChildStats) -> Float {
+        let range = child.max_reward - child.min_reward;
+        let q = match range > 0. {
+            true => (child.wi / child.ni - child.min_reward) / range,
+            false => 0.5,
+        };
+        q + self.c * (child.np.ln() / child.ni).sqrt()
+    }
+}
+
+/// UCT tree policy for rewards on any scale, like game scores or sums of step rewards: the mean value of each child is
+/// mapped into `[0, 1]` by the smallest and largest rewards observed among its siblings before adding the exploration term,
+/// so that `c` can be chosen as for rewards in `[0, 1]`, e.g. `sqrt(2)`.
+/// The parallel search tree of the `parallel` feature doesn't observe the rewards, and gives this policy a range of `[0, 1]`.
+pub fn uct_normalized(c: Float) -> UctNormalized {
+    UctNormalized { c }
+}
+
+/// PUCT tree policy used by AlphaZero, see [`puct`].
+#[derive(Debug, Clone, Copy)]
+pub struct Puct {
+    /// The exploration constant
+    pub c_puct: Float,
+}
+
+impl TreePolicy for Puct {
+    fn score(&self, child: &ChildStats) -> Float {
+        let q = if child.ni > 0. {
+            child.wi / child.ni
+        } else {
+            0.
+        };
+        q + self.c_puct * child.prior * child.np.sqrt() / (1. + child.ni)
+    }
+}
+
+/// PUCT tree policy `Q(s,a) + c_puct * P(s,a) * sqrt(N(s)) / (1 + N(s,a))`, guided by the priors `P(s,a)`
+/// given by [`crate::SearchTree::with_priors`].
+/// Like [`uct`], it assumes rewards in `[0, 1]`.
+pub fn puct(c_puct: Float) -> Puct {
+    Puct { c_puct }
+}
+
+/// RAVE tree policy, see [`rave`].
+#[derive(Debug, Clone, Copy)]
+pub struct Rave {
+    /// The exploration constant of the UCT term
+    pub c: Float,
+    /// The equivalence parameter: the number of visits at which the AMAF value and the child's own value weigh the same
+    pub k: Float,
+}
+
+impl TreePolicy for Rave {
+    fn score(&self, child: &ChildStats) -> Float {
+        let q = child.wi / child.ni;
+        let beta = match child.amaf_ni > 0. {
+            true => (self.k / (3. * child.ni + self.k)).sqrt(),
+            false => 0.,
+        };
+        let amaf_q = match child.amaf_ni > 0. {
+            true => child.amaf_wi / child.amaf_ni,
+            false => 0.,
+        };
+        (1. - beta) * q + beta * amaf_q + self.c * (child.np.ln() / child.ni).sqrt()
+    }
+}
+
+/// RAVE tree policy `(1 - β) * Q(s,a) + β * AMAF(s,a) + c * sqrt(ln N(s) / N(s,a))`, which blends the child's own value
+/// with its all-moves-as-first value, with `β = sqrt(k / (3 * N(s,a) + k))` fading the AMAF value out as the child is visited.
+/// The AMAF statistics are only recorded with [`crate::SearchTree::with_amaf`].
+/// Like [`uct`], it assumes rewards in `[0, 1]`.
+pub fn rave(c: Float, k: Float) -> Rave {
+    Rave { c, k }
+}
+
+/// GRAVE tree policy, see [`grave`].
+#[derive(Debug, Clone, Copy)]
+pub struct Grave {
+    /// The exploration constant of the UCT term
+    pub c: Float,
+    /// The visits from which a node becomes the reference node
+    pub ref_visits: Float,
+    /// The bias of `β`, which makes the AMAF value fade out faster the larger it is
+    pub bias: Float,
+}
+
+impl TreePolicy for Grave {
+    fn score(&self, child: &ChildStats) -> Float {
+        let q = child.wi / child.ni;
+        let (amaf_wi, amaf_ni) = (child.ref_amaf_wi, child.ref_amaf_ni);
+        let (beta, amaf_q) = match amaf_ni > 0. {
+            true => (
+                amaf_ni / (amaf_ni + child.ni + self.bias * amaf_ni * child.ni),
+                amaf_wi / amaf_ni,
+            ),
+            false => (0., 0.),
+        };
+        (1. - beta) * q + beta * amaf_q + self.c * (child.np.ln() / child.ni).sqrt()
+    }
+
+    fn reference_visits(&self) -> Option<Float> {
+        Some(self.ref_visits)
+    }
+}
+
+/// GRAVE (generalized RAVE) tree policy, which blends the child's own value with the AMAF value of its action like [`rave`],
+/// but takes the AMAF value from the reference node: the deepest node of the path with at least `ref_visits` visits,
+/// whose statistics are more reliable than those of rarely visited nodes. The root is the reference until a node below it
+/// has been visited often enough. `β = m / (m + N(s,a) + bias * m * N(s,a))` weighs the AMAF value, where `m` is the number
+/// of simulations of the AMAF value, and the bias is `1e-5`; set [`Grave::bias`] to change it.
+/// The AMAF statistics are only recorded with [`crate::SearchTree::with_amaf`], and the reference node is only tracked by
+/// [`crate::SearchTree`]. Like [`uct`], it assumes rewards in `[0, 1]`.
+pub fn grave(c: Float, ref_visits: Float) -> Grave {
+    Grave {
+        c,
+        ref_visits,
+        bias: 1e-5,
+    }
+}
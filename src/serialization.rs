@@ -0,0 +1,211 @@
+//! Serialization of the search tree, enabled by the `serde` feature.
+//!
+//! A tree is serialized as the sequence of its nodes in preorder, each with its state, last action, statistics and
+//! number of children, so that trees of any depth are serialized and deserialized without recursing. The tree policy,
+//! the leaf evaluation and the priors callback can't be serialized: a deserialized tree uses the defaults of
+//! [`SearchTree::new`], and they have to be set again with the `with_*` methods before continuing the search; so does
+//! the index of the children by action of `SearchTree::with_action_index`, without which the children are found by a
+//! scan. The outcomes backed up by [`SearchTree::with_implicit_minimax`] aren't serialized either, and are backed up
+//! again as the nodes are visited. Subtrees shared through the transposition table are serialized once per parent and
+//! are no longer shared after deserializing.
+
+use alloc::{
+    rc::{Rc, Weak},
+    vec,
+    vec::Vec,
+};
+use core::cell::{Cell, RefCell};
+
+use serde::{
+    de::Error,
+    ser::{SerializeSeq, SerializeStruct},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{uct, Float, GameState, Node, SearchTree, TreePolicy};
+
+/// A node without its children, which follow it in the preorder sequence of the nodes of the tree.
+struct NodeRecord<'a, G: GameState>(&'a Node<G>);
+
+impl<G> Serialize for NodeRecord<'_, G>
+where
+    G: GameState + Serialize,
+    G::Action: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let NodeRecord(node) = self;
+        let mut record = serializer.serialize_struct("Node", 14)?;
+        record.serialize_field("state", node.state.as_ref())?;
+        record.serialize_field("last_action", &node.last_action)?;
+        record.serialize_field("wi", &node.wi.get())?;
+        record.serialize_field("ni", &node.ni.get())?;
+        record.serialize_field("sum_sq", &node.sum_sq.get())?;
+        record.serialize_field("prior", &node.prior.get())?;
+        record.serialize_field("pending", &*node.pending.borrow())?;
+        record.serialize_field("availability", &node.availability.get())?;
+        record.serialize_field("amaf_wi", &node.amaf_wi.get())?;
+        record.serialize_field("amaf_ni", &node.amaf_ni.get())?;
+        record.serialize_field("reward_range", &node.reward_range.get())?;
+        record.serialize_field("mover_rewards", &*node.mover_rewards.borrow())?;
+        record.serialize_field("backed_up", &node.backed_up.get())?;
+        record.serialize_field("children", &node.child_nodes.borrow().len())?;
+        record.end()
+    }
+}
+
+impl<G> Serialize for Node<G>
+where
+    G: GameState + Serialize,
+    G::Action: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // The descendants in preorder, the children of each node being pushed in reverse to be popped in order
+        let mut descendants = vec![];
+        let mut nodes: Vec<_> = self.child_nodes().into_iter().rev().collect();
+        while let Some(node) = nodes.pop() {
+            nodes.extend(node.borrow().child_nodes().into_iter().rev());
+            descendants.push(node);
+        }
+        let mut records = serializer.serialize_seq(Some(descendants.len() + 1))?;
+        records.serialize_element(&NodeRecord(self))?;
+        for node in &descendants {
+            records.serialize_element(&NodeRecord(&*node.borrow()))?;
+        }
+        records.end()
+    }
+}
+
+/// The serialized form of a [`Node`] without its children, see [`NodeRecord`].
+#[derive(Deserialize)]
+struct NodeData<G, A> {
+    state: G,
+    last_action: Option<A>,
+    wi: Float,
+    ni: Float,
+    sum_sq: Float,
+    prior: f32,
+    pending: Vec<(A, f32)>,
+    availability: Float,
+    amaf_wi: Float,
+    amaf_ni: Float,
+    reward_range: Option<(Float, Float)>,
+    #[serde(default)]
+    mover_rewards: Vec<(Float, Float)>,
+    #[serde(default)]
+    backed_up: Option<Float>,
+    children: usize,
+}
+
+impl<G: GameState> Node<G> {
+    /// The node of `data`, without children yet.
+    fn from_data(data: NodeData<G, G::Action>, tree_policy: &Rc<dyn TreePolicy>) -> Self {
+        Node {
+            state: Rc::new(data.state),
+            last_action: data.last_action,
+            child_nodes: Rc::default(),
+            #[cfg(feature = "action-index")]
+            hash_action: None,
+            #[cfg(feature = "action-index")]
+            child_index: Rc::default(),
+            end_status: Default::default(),
+            solved: Default::default(),
+            wi: Cell::new(data.wi),
+            ni: Cell::new(data.ni),
+            sum_sq: Cell::new(data.sum_sq),
+            prior: Cell::new(data.prior),
+            pending: RefCell::new(data.pending),
+            availability: Cell::new(data.availability),
+            amaf_wi: Cell::new(data.amaf_wi),
+            amaf_ni: Cell::new(data.amaf_ni),
+            reward_range: Cell::new(data.reward_range),
+            mover_rewards: RefCell::new(data.mover_rewards),
+            backed_up: Cell::new(data.backed_up),
+            minimax_outcome: RefCell::new(None),
+            tree_policy: tree_policy.clone(),
+            this: Weak::new(),
+            parent: RefCell::new(Weak::new()),
+        }
+    }
+
+    /// Rebuild the tree of the nodes of `records` in preorder, or `None` if they don't make exactly one tree.
+    fn from_records(
+        records: Vec<NodeData<G, G::Action>>,
+        tree_policy: &Rc<dyn TreePolicy>,
+    ) -> Option<Self> {
+        // The nodes whose children are still being read, with the number of children left to read
+        let mut ancestors: Vec<(Node<G>, usize)> = vec![];
+        let mut records = records.into_iter();
+        while let Some(data) = records.next() {
+            let children = data.children;
+            ancestors.push((Node::from_data(data, tree_policy), children));
+            // A node whose children have all been read is done, and becomes a child of the node before it
+            while let Some((_, 0)) = ancestors.last() {
+                let (node, _) = ancestors.pop()?;
+                match ancestors.last_mut() {
+                    Some((parent, children)) => {
+                        parent.child_nodes.borrow_mut().push(node.into_rc());
+                        *children -= 1;
+                    }
+                    None => return records.next().is_none().then_some(node),
+                }
+            }
+        }
+        None
+    }
+}
+
+/// A deserialized node selects its children by UCT, like the nodes of a new [`SearchTree`].
+impl<'de, G> Deserialize<'de> for Node<G>
+where
+    G: GameState + Deserialize<'de>,
+    G::Action: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let records = Vec::<NodeData<G, G::Action>>::deserialize(deserializer)?;
+        Node::from_records(records, &(Rc::new(uct) as Rc<dyn TreePolicy>)).ok_or_else(|| {
+            D::Error::custom("the nodes don't make a tree: their numbers of children don't add up")
+        })
+    }
+}
+
+/// Only the root node, the ISMCTS mode and the tiebreak mode are serialized.
+impl<G> Serialize for SearchTree<G>
+where
+    G: GameState + Serialize,
+    G::Action: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tree = serializer.serialize_struct("SearchTree", 3)?;
+        tree.serialize_field("root_node", &*self.root_node.borrow())?;
+        tree.serialize_field("ismcts", &self.ismcts)?;
+        tree.serialize_field("random_tiebreak", &self.random_tiebreak)?;
+        tree.end()
+    }
+}
+
+/// The serialized form of a [`SearchTree`].
+#[derive(Deserialize)]
+#[serde(bound = "Node<G>: Deserialize<'de>")]
+struct SearchTreeData<G: GameState> {
+    root_node: Node<G>,
+    ismcts: bool,
+    random_tiebreak: bool,
+}
+
+/// The deserialized tree has the default configuration of [`SearchTree::new`] besides the serialized modes;
+/// set the tree policy, the leaf evaluation, the priors and the RNG again if they were customized.
+impl<'de, G> Deserialize<'de> for SearchTree<G>
+where
+    G: GameState + Deserialize<'de>,
+    G::Action: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data: SearchTreeData<G> = SearchTreeData::deserialize(deserializer)?;
+        let mut search_tree = SearchTree::new(data.root_node.state.clone());
+        search_tree.root_node = data.root_node.into_rc();
+        search_tree.nodes.set(Node::count(&search_tree.root_node));
+        search_tree.ismcts = data.ismcts;
+        search_tree.random_tiebreak = data.random_tiebreak;
+        Ok(search_tree)
+    }
+}
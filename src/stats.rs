@@ -0,0 +1,63 @@
+use std::sync::{Arc, Mutex};
+
+use crate::Float;
+
+/// A snapshot of the statistics of the root of a search tree, as published to its [`StatsHandle`]s.
+#[derive(Debug, Clone)]
+pub struct RootStats<A> {
+    /// The action chosen by [`crate::SearchTree::best_action`], or `None` if the root has no visited child;
+    /// ties are broken by the order of the children, without the RNG of [`crate::SearchTree::with_random_tiebreak`]
+    pub best_action: Option<A>,
+    /// Total reward of the root
+    pub wi: Float,
+    /// Times the root has been selected
+    pub ni: Float,
+    /// Number of simulations run since the root was set, see [`crate::SearchTree::simulations_done`]
+    pub simulations: u32,
+    /// Number of nodes in the tree, see [`crate::SearchTree::node_count`]
+    pub nodes: usize,
+    /// The action, mean value and times of selection of each child of the root, like
+    /// [`crate::SearchTree::root_action_values`]
+    pub children: Vec<(A, Float, Float)>,
+}
+
+/// A handle to the latest statistics of the root of a search tree, given by [`crate::SearchTree::stats_handle`].
+///
+/// The search tree itself isn't `Send`, since its nodes are `Rc<RefCell>`s, so it has to stay on the task or thread
+/// that searches with it; the parallel search tree of the `parallel` feature is the one that can be shared. The
+/// handle is `Send + Sync` if the actions are `Send`, and cheap to clone: it holds the latest [`RootStats`] behind an
+/// `Arc<Mutex>`, which the tree replaces after every call that runs simulations, so another task or thread can poll it,
+/// e.g. to show the progress of a search driven in steps by an async runtime, without borrowing the tree.
+#[derive(Debug)]
+pub struct StatsHandle<A> {
+    stats: Arc<Mutex<RootStats<A>>>,
+}
+
+impl<A> StatsHandle<A> {
+    pub(crate) fn new(stats: RootStats<A>) -> Self {
+        StatsHandle {
+            stats: Arc::new(Mutex::new(stats)),
+        }
+    }
+
+    pub(crate) fn publish(&self, stats: RootStats<A>) {
+        *self.stats.lock().unwrap() = stats;
+    }
+}
+
+impl<A: Clone> StatsHandle<A> {
+    /// The statistics published last, which are those after the last call of the tree that ran simulations,
+    /// or those when the handle was created if none has run since.
+    pub fn get(&self) -> RootStats<A> {
+        self.stats.lock().unwrap().clone()
+    }
+}
+
+// Implemented by hand, since deriving would require `A: Clone`
+impl<A> Clone for StatsHandle<A> {
+    fn clone(&self) -> Self {
+        StatsHandle {
+            stats: self.stats.clone(),
+        }
+    }
+}
@@ -0,0 +1,452 @@
+//! Tests of the search on small games.
+
+use super::*;
+
+/// The end status of the games below, valued for the first player
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Outcome {
+    First,
+    Second,
+    Draw,
+}
+
+impl EndStatus for Outcome {}
+
+fn outcome_value(outcome: &Outcome) -> f32 {
+    match outcome {
+        Outcome::First => 1.,
+        Outcome::Second => 0.,
+        Outcome::Draw => 0.5,
+    }
+}
+
+/// One of `width` steps at every turn, with the game ending in a draw after `length` steps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Step(u8);
+
+impl Action for Step {}
+
+#[derive(Clone)]
+struct Walk {
+    steps: u32,
+    length: u32,
+    width: u8,
+}
+
+impl Walk {
+    fn new(length: u32, width: u8) -> Self {
+        Walk {
+            steps: 0,
+            length,
+            width,
+        }
+    }
+}
+
+impl ZeroSumGameState<Outcome> for Walk {
+    fn outcome_value(outcome: &Outcome) -> f32 {
+        outcome_value(outcome)
+    }
+}
+
+impl GameState for Walk {
+    type Player = ZeroSumPlayer<Walk>;
+    type EndStatus = Outcome;
+    type Action = Step;
+
+    fn player(&self) -> Self::Player {
+        match self.steps % 2 {
+            0 => ZeroSumPlayer::first(),
+            _ => ZeroSumPlayer::second(),
+        }
+    }
+
+    fn end_status(&self) -> Option<Outcome> {
+        (self.steps == self.length).then_some(Outcome::Draw)
+    }
+
+    fn possible_actions(&self) -> Vec<Step> {
+        (0..self.width).map(Step).collect()
+    }
+
+    fn act(&self, _: &Step) -> Self {
+        Walk {
+            steps: self.steps + 1,
+            ..self.clone()
+        }
+    }
+}
+
+/// The visits of the root's child of `action`
+fn visits_of<G: GameState>(tree: &SearchTree<G>, action: &G::Action) -> Float {
+    tree.child_for(action)
+        .map_or(0., |child| child.borrow().ni())
+}
+
+#[test]
+fn ties_are_broken_by_the_order_of_the_actions() {
+    let tree = SearchTree::new(Rc::new(Walk::new(1, 2))).with_random_tiebreak(false);
+    assert_eq!(tree.search(10), Some(Step(0)));
+    assert_eq!(visits_of(&tree, &Step(0)), 5.);
+    assert_eq!(visits_of(&tree, &Step(1)), 5.);
+}
+
+#[test]
+fn random_ties_are_reproducible_with_the_same_seed() {
+    let decide = |seed| {
+        let tree = SearchTree::new(Rc::new(Walk::new(1, 2)))
+            .with_rng(StdRng::seed_from_u64(seed))
+            .with_random_tiebreak(true);
+        let action = tree.search(10);
+        assert_eq!(visits_of(&tree, &Step(0)), visits_of(&tree, &Step(1)));
+        action
+    };
+    let actions: Vec<_> = (0..16).map(decide).collect();
+    assert_eq!(actions, (0..16).map(decide).collect::<Vec<_>>());
+    assert!(actions.contains(&Some(Step(0))));
+    assert!(actions.contains(&Some(Step(1))));
+}
+
+#[test]
+fn a_deep_game_neither_overflows_the_search_nor_the_drop() {
+    let tree = SearchTree::new(Rc::new(Walk::new(100_000, 1)));
+    assert_eq!(tree.search(1), Some(Step(0)));
+    assert_eq!(tree.node_count(), 100_001);
+    drop(tree);
+}
+
+#[test]
+#[should_panic(expected = "has no possible actions")]
+fn a_state_without_moves_that_has_not_ended_panics() {
+    SearchTree::new(Rc::new(Walk::new(1, 0))).search(1);
+}
+
+/// Taking a stone of a pile, or passing once it is empty; the game ends in a draw after two passes in a row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Move {
+    Take,
+    Pass,
+}
+
+impl Action for Move {}
+
+#[derive(Clone)]
+struct Pile {
+    stones: u32,
+    passes: u32,
+    first: bool,
+}
+
+impl ZeroSumGameState<Outcome> for Pile {
+    fn outcome_value(outcome: &Outcome) -> f32 {
+        outcome_value(outcome)
+    }
+}
+
+impl GameState for Pile {
+    type Player = ZeroSumPlayer<Pile>;
+    type EndStatus = Outcome;
+    type Action = Move;
+
+    fn player(&self) -> Self::Player {
+        match self.first {
+            true => ZeroSumPlayer::first(),
+            false => ZeroSumPlayer::second(),
+        }
+    }
+
+    fn end_status(&self) -> Option<Outcome> {
+        (self.passes == 2).then_some(Outcome::Draw)
+    }
+
+    fn possible_actions(&self) -> Vec<Move> {
+        match self.stones {
+            0 => vec![],
+            _ => vec![Move::Take],
+        }
+    }
+
+    fn pass_action(&self) -> Option<Move> {
+        Some(Move::Pass)
+    }
+
+    fn act(&self, action: &Move) -> Self {
+        let (stones, passes) = match action {
+            Move::Take => (self.stones - 1, 0),
+            Move::Pass => (self.stones, self.passes + 1),
+        };
+        Pile {
+            stones,
+            passes,
+            first: !self.first,
+        }
+    }
+}
+
+#[test]
+fn two_passes_end_the_game() {
+    let pile = Pile {
+        stones: 0,
+        passes: 0,
+        first: true,
+    };
+    let tree = SearchTree::new(Rc::new(pile.clone()));
+    assert_eq!(tree.search(10), Some(Move::Pass));
+    assert_eq!(tree.principal_variation(), vec![Move::Pass, Move::Pass]);
+    assert_eq!(tree.node_count(), 3);
+
+    let pile = Pile { stones: 2, ..pile };
+    assert_eq!(play_game(Rc::new(pile), 10), Outcome::Draw);
+}
+
+/// Taking one or two stones of a pile, where the player taking the last one wins
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Take(u32);
+
+impl Action for Take {}
+
+#[derive(Clone)]
+struct Nim {
+    stones: u32,
+    first: bool,
+}
+
+impl Nim {
+    fn new(stones: u32) -> Self {
+        Nim {
+            stones,
+            first: true,
+        }
+    }
+}
+
+impl ZeroSumGameState<Outcome> for Nim {
+    fn outcome_value(outcome: &Outcome) -> f32 {
+        outcome_value(outcome)
+    }
+}
+
+impl GameState for Nim {
+    type Player = ZeroSumPlayer<Nim>;
+    type EndStatus = Outcome;
+    type Action = Take;
+
+    fn player(&self) -> Self::Player {
+        match self.first {
+            true => ZeroSumPlayer::first(),
+            false => ZeroSumPlayer::second(),
+        }
+    }
+
+    fn end_status(&self) -> Option<Outcome> {
+        // The player who took the last stone is the one who isn't to move
+        match (self.stones, self.first) {
+            (0, true) => Some(Outcome::Second),
+            (0, false) => Some(Outcome::First),
+            _ => None,
+        }
+    }
+
+    fn possible_actions(&self) -> Vec<Take> {
+        (1..=self.stones.min(2)).map(Take).collect()
+    }
+
+    fn act(&self, action: &Take) -> Self {
+        Nim {
+            stones: self.stones - action.0,
+            first: !self.first,
+        }
+    }
+}
+
+#[test]
+fn nan_scores_count_as_negative_infinity() {
+    assert_eq!(argmax(vec![(0, Float::NAN), (1, 0.5)], None), Some(1));
+    assert_eq!(
+        argmax(vec![(0, Float::NAN), (1, Float::NEG_INFINITY)], None),
+        Some(0)
+    );
+    assert_eq!(
+        argmax(vec![(0, Float::NAN), (1, Float::NAN)], None),
+        Some(0)
+    );
+    assert_eq!(argmax(Vec::<(u8, Float)>::new(), None), None);
+}
+
+#[test]
+fn infinite_scores_win_over_finite_ones() {
+    assert_eq!(
+        argmax(vec![(0, Float::MAX), (1, Float::INFINITY)], None),
+        Some(1)
+    );
+    assert_eq!(
+        argmax(vec![(0, Float::NEG_INFINITY), (1, Float::MIN)], None),
+        Some(1)
+    );
+}
+
+/// The visits of the children of the root of two stones, taking one that loses and two that wins, after the first two
+/// simulations visited each once and the next eight were selected by a policy scoring them by `winning` and `losing`
+fn visits_with_winning_move_scored(winning: Float, losing: Float) -> (Float, Float) {
+    let policy = move |wi: Float, _: Float, _: Float| if wi > 0. { winning } else { losing };
+    let tree = SearchTree::new(Rc::new(Nim::new(2))).with_tree_policy(policy);
+    tree.search(10);
+    (visits_of(&tree, &Take(1)), visits_of(&tree, &Take(2)))
+}
+
+#[test]
+fn the_tree_policy_may_score_nan_and_infinity() {
+    assert_eq!(visits_with_winning_move_scored(Float::NAN, 0.5), (9., 1.));
+    assert_eq!(
+        visits_with_winning_move_scored(Float::INFINITY, 0.5),
+        (1., 9.)
+    );
+    assert_eq!(
+        visits_with_winning_move_scored(0.5, Float::NEG_INFINITY),
+        (1., 9.)
+    );
+    // Tied at negative infinity, broken by the order of the actions
+    assert_eq!(
+        visits_with_winning_move_scored(Float::NEG_INFINITY, Float::NAN),
+        (9., 1.)
+    );
+}
+
+#[test]
+fn renewing_a_clone_leaves_the_original_tree_unaffected() {
+    let tree = SearchTree::new(Rc::new(Nim::new(5)));
+    tree.search(200);
+    let (nodes, visits) = (tree.node_count(), visits_of(&tree, &Take(2)));
+
+    let mut fork = tree.clone();
+    fork.renew(&Take(2)).unwrap();
+    fork.search(100);
+    assert_eq!(fork.get_game_state().stones, 3);
+
+    assert_eq!(tree.get_game_state().stones, 5);
+    assert_eq!(tree.simulations_done(), 200);
+    assert_eq!(tree.node_count(), nodes);
+    assert_eq!(visits_of(&tree, &Take(2)), visits);
+    assert_eq!(tree.best_action(), Some(Take(2)));
+}
+
+#[test]
+fn a_reset_tree_searches_the_new_game() {
+    let mut tree = SearchTree::new(Rc::new(Nim::new(4))).with_tree_policy(uct_with_c(1.));
+    assert_eq!(tree.search(200), Some(Take(1)));
+
+    tree.reset(Rc::new(Nim::new(5)));
+    assert_eq!(tree.node_count(), 1);
+    assert_eq!(tree.simulations_done(), 0);
+    assert_eq!(tree.search(200), Some(Take(2)));
+    assert_eq!(tree.simulations_done(), 200);
+}
+
+impl Transposable for Nim {
+    type Key = (u32, bool);
+
+    fn transposition_key(&self) -> (u32, bool) {
+        (self.stones, self.first)
+    }
+}
+
+/// The visits of the nodes of a tree of Nim by the stones taken from the root to reach them
+fn visits_by_path(tree: &SearchTree<Nim>) -> HashMap<Vec<u32>, Float> {
+    let mut visits = HashMap::new();
+    let mut nodes = vec![(Vec::new(), tree.root_node())];
+    while let Some((path, node)) = nodes.pop() {
+        let node = node.borrow();
+        for child in node.child_nodes() {
+            let mut path = path.clone();
+            path.push(child.borrow().last_action.unwrap().0);
+            nodes.push((path, child));
+        }
+        visits.insert(path, node.ni());
+    }
+    visits
+}
+
+#[test]
+fn merging_sums_the_visits_of_the_nodes_matched_by_their_actions() {
+    let mut tree = SearchTree::new(Rc::new(Nim::new(6)));
+    tree.search(3);
+    let other = SearchTree::new(Rc::new(Nim::new(6))).with_rng(StdRng::seed_from_u64(1));
+    other.search(100);
+    let (visits, other_visits) = (visits_by_path(&tree), visits_by_path(&other));
+
+    tree.merge(&other);
+    let merged = visits_by_path(&tree);
+    assert!(visits.len() < other_visits.len());
+    // The nodes only in one of the trees are kept
+    for path in visits.keys().chain(other_visits.keys()) {
+        let sum = visits.get(path).unwrap_or(&0.) + other_visits.get(path).unwrap_or(&0.);
+        assert_eq!(merged[path], sum, "visits of {path:?}");
+    }
+    assert_eq!(merged.len(), other_visits.len());
+    assert_eq!(tree.simulations_done(), 103);
+    assert_eq!(tree.node_count(), merged.len());
+}
+
+#[test]
+fn merging_adds_the_shared_children_of_a_position_once() {
+    let mut tree = SearchTree::new(Rc::new(Nim::new(6))).with_transposition_table();
+    let other = SearchTree::new(Rc::new(Nim::new(6))).with_transposition_table();
+    other.search(300);
+    tree.merge(&other);
+
+    let position = |tree: &SearchTree<Nim>, first: u32, second: u32| {
+        let node = tree.child_for(&Take(first)).unwrap();
+        let node = node.borrow().child(&Take(second)).unwrap();
+        node
+    };
+    // Both orders of taking one and two stones reach the same position, which shares its children
+    let (node, transposed) = (position(&tree, 1, 2), position(&tree, 2, 1));
+    assert!(Rc::ptr_eq(
+        &node.borrow().child_nodes,
+        &transposed.borrow().child_nodes
+    ));
+    let other_node = position(&other, 1, 2);
+    assert_eq!(node.borrow().ni(), other_node.borrow().ni());
+    assert_eq!(
+        transposed.borrow().ni(),
+        position(&other, 2, 1).borrow().ni()
+    );
+    for take in [Take(1), Take(2)] {
+        let child = node.borrow().child(&take).unwrap();
+        let other_child = other_node.borrow().child(&take).unwrap();
+        assert_eq!(child.borrow().ni(), other_child.borrow().ni());
+        assert!(child.borrow().ni() > 0.);
+    }
+}
+
+#[test]
+fn sequential_halving_descends_into_a_candidate_from_the_first_simulation() {
+    let tree = SearchTree::new(Rc::new(Nim::new(4))).with_rollout_policy(RandomRollout);
+    tree.search_sequential_halving(64);
+    let root_visits = tree.root_node().borrow().ni();
+    let child_visits: Float = [Take(1), Take(2)]
+        .iter()
+        .map(|take| visits_of(&tree, take))
+        .sum();
+    assert_eq!(root_visits, 64.);
+    assert_eq!(child_visits, 64.);
+}
+
+#[test]
+fn the_first_decision_of_a_last_good_reply_rollout_replies_to_the_last_recorded_move() {
+    let lgr = LgrRollout::new();
+    let state = Nim {
+        stones: 1,
+        first: false,
+    };
+    let mut rng = StdRng::seed_from_u64(0);
+    RolloutPolicy::<Nim>::rollout(&lgr, &state, &mut rng);
+    assert_eq!(lgr.reply_count(), 0);
+
+    // The second player wins by taking the last stone, which replies to the first player taking two
+    let mut moves = vec![(ZeroSumPlayer::first(), Take(2))];
+    let outcome = RolloutPolicy::<Nim>::rollout_recorded(&lgr, &state, &mut rng, &mut moves);
+    assert_eq!(outcome, Outcome::Second);
+    assert_eq!(lgr.reply_count(), 1);
+    assert_eq!(lgr.replies.borrow().get(&Take(2)), Some(&Take(1)));
+}
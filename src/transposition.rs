@@ -0,0 +1,97 @@
+use alloc::rc::{Rc, Weak};
+use core::{cell::RefCell, hash::Hash};
+
+#[cfg(not(feature = "std"))]
+use hashbrown::HashMap;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::{GameState, NodeCopies, RcNode, WeakNode};
+
+/// The trait for game states whose positions can be reached by different sequences of actions, used by
+/// [`crate::SearchTree::with_transposition_table`] to recognize them.
+pub trait Transposable {
+    /// A key identifying the position, e.g. a Zobrist hash or the board itself.
+    type Key: Hash + Eq;
+
+    /// The key of the position. Two states must have the same key only if they are the same for the rest of the game,
+    /// including the player to move and anything the end status depends on. In ISMCTS, where the states are worlds
+    /// sampled by [`crate::GameState::determinize`], it should instead identify the information set of the player to
+    /// move, leaving out what it can't observe.
+    fn transposition_key(&self) -> Self::Key;
+}
+
+/// The type-erased interface of [`TranspositionTable`], so that the search tree doesn't need to name the key type.
+pub(crate) trait Transpositions<G: GameState> {
+    /// Make `node` share the children of the first node reached with the same position,
+    /// or remember it as that first node if there is none.
+    fn share(&self, node: &RcNode<G>);
+
+    /// Forget the nodes that have been dropped, e.g. after the root was renewed.
+    fn prune(&self);
+
+    /// A table of the same positions for a copy of the tree, whose nodes are given by the nodes they copy.
+    fn copy(&self, copies: &NodeCopies<G>) -> Rc<dyn Transpositions<G>>;
+}
+
+/// Nodes by the key of their state. The nodes are held weakly, so that the table doesn't keep pruned subtrees alive.
+pub(crate) struct TranspositionTable<K, G: GameState> {
+    nodes: RefCell<HashMap<K, WeakNode<G>>>,
+}
+
+impl<K, G: GameState> TranspositionTable<K, G> {
+    pub(crate) fn new() -> Self {
+        TranspositionTable {
+            nodes: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+impl<G> Transpositions<G> for TranspositionTable<G::Key, G>
+where
+    G: GameState + Transposable + 'static,
+{
+    fn share(&self, node: &RcNode<G>) {
+        let key = node.borrow().state.transposition_key();
+        let mut nodes = self.nodes.borrow_mut();
+        let first = nodes.get(&key).and_then(Weak::upgrade);
+        match first {
+            Some(first) if !Rc::ptr_eq(&first, node) => {
+                let first = first.borrow();
+                let mut node = node.borrow_mut();
+                node.state = first.state.clone();
+                node.child_nodes = first.child_nodes.clone();
+                #[cfg(feature = "action-index")]
+                {
+                    node.child_index = first.child_index.clone();
+                }
+            }
+            Some(_) => {}
+            None => {
+                nodes.insert(key, Rc::downgrade(node));
+            }
+        }
+    }
+
+    fn prune(&self) {
+        self.nodes
+            .borrow_mut()
+            .retain(|_, node| node.strong_count() > 0);
+    }
+
+    fn copy(&self, copies: &NodeCopies<G>) -> Rc<dyn Transpositions<G>> {
+        let nodes = self
+            .nodes
+            .borrow()
+            .values()
+            .filter_map(|node| {
+                let copy = copies.get(&node.as_ptr())?;
+                let key = copy.borrow().state.transposition_key();
+                Some((key, Rc::downgrade(copy)))
+            })
+            .collect();
+        Rc::new(TranspositionTable {
+            nodes: RefCell::new(nodes),
+        })
+    }
+}
@@ -0,0 +1,101 @@
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+use crate::{EndStatus, Player};
+
+/// The trait for two-player zero-sum games whose outcomes are valued from the first player's perspective only.
+/// The players of such a game are [`ZeroSumPlayer`]s, which derive the reward of the second player from the value
+/// of the first, so the rewards of the two players can't disagree: implement [`crate::GameState`] for the game with
+/// `ZeroSumPlayer<Self>` as its `Player`, returning [`ZeroSumPlayer::first`] or [`ZeroSumPlayer::second`] from
+/// [`crate::GameState::player`].
+pub trait ZeroSumGameState<E: EndStatus> {
+    /// The value of the outcome for the first player in `[0, 1]`, e.g. 1 if it wins, 0.5 for a tie and 0 if it loses.
+    /// The reward of the second player is `1 - value`.
+    fn outcome_value(outcome: &E) -> f32;
+}
+
+/// One of the two players of the [`ZeroSumGameState`] `G`, whose rewards are given by [`ZeroSumGameState::outcome_value`].
+pub struct ZeroSumPlayer<G> {
+    first: bool,
+    game: PhantomData<fn() -> G>,
+}
+
+impl<G> ZeroSumPlayer<G> {
+    /// The first player, whose reward is the value of the outcome.
+    pub const fn first() -> Self {
+        ZeroSumPlayer {
+            first: true,
+            game: PhantomData,
+        }
+    }
+
+    /// The second player, whose reward is one minus the value of the outcome.
+    pub const fn second() -> Self {
+        ZeroSumPlayer {
+            first: false,
+            game: PhantomData,
+        }
+    }
+
+    /// The other player, e.g. the player to move after this one.
+    pub const fn opponent(&self) -> Self {
+        ZeroSumPlayer {
+            first: !self.first,
+            game: PhantomData,
+        }
+    }
+
+    /// Whether this is the first player.
+    pub const fn is_first(&self) -> bool {
+        self.first
+    }
+}
+
+impl<G, E> Player<E> for ZeroSumPlayer<G>
+where
+    G: ZeroSumGameState<E>,
+    E: EndStatus,
+{
+    fn reward_when_outcome_is(&self, outcome: &E) -> f32 {
+        let value = G::outcome_value(outcome);
+        match self.first {
+            true => value,
+            false => 1. - value,
+        }
+    }
+}
+
+// Implemented by hand, since deriving would require the same traits of the game
+impl<G> Clone for ZeroSumPlayer<G> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<G> Copy for ZeroSumPlayer<G> {}
+
+impl<G> PartialEq for ZeroSumPlayer<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.first == other.first
+    }
+}
+
+impl<G> Eq for ZeroSumPlayer<G> {}
+
+impl<G> Hash for ZeroSumPlayer<G> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.first.hash(state);
+    }
+}
+
+impl<G> fmt::Debug for ZeroSumPlayer<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.first {
+            true => f.write_str("First"),
+            false => f.write_str("Second"),
+        }
+    }
+}